@@ -0,0 +1,91 @@
+/**
+ * Renderer micro-benchmark: time full-screen colored redraws of a synthetic
+ * viewport with the glyph atlas and with batched fillText. `npm run bench`.
+ */
+
+import {
+  EXPECTED_CELL_SIZE,
+  FLAG_BOLD,
+  FLAG_FG_EXPLICIT,
+  MiniRenderer,
+  resolveTheme,
+  type ViewportData,
+} from "../src/core/libghostty.js";
+
+const COLS = 200;
+const ROWS = 60;
+const FRAMES = 8;
+const WARMUP = 10;
+const SAMPLES = 120;
+const PALETTE = [
+  [247, 118, 142],
+  [158, 206, 106],
+  [224, 175, 104],
+  [122, 162, 247],
+  [187, 154, 247],
+  [125, 207, 255],
+  [192, 202, 245],
+  [86, 95, 137],
+];
+const CHARS = "abcdefghijklmnopqrstuvwxyz0123456789";
+
+/** Deterministic frames: short runs of one palette color, every fourth run bold. */
+function makeFrame(seed: number): ViewportData {
+  const buffer = new Uint8Array(COLS * ROWS * EXPECTED_CELL_SIZE);
+  const view = new DataView(buffer.buffer);
+  let state = seed * 2654435761;
+  const next = () => {
+    state = (Math.imul(state, 1664525) + 1013904223) >>> 0;
+    return state;
+  };
+  let runLeft = 0;
+  let color = PALETTE[0];
+  let flags = FLAG_FG_EXPLICIT;
+  for (let i = 0; i < COLS * ROWS; i++) {
+    if (runLeft === 0) {
+      runLeft = 3 + (next() % 12);
+      color = PALETTE[next() % PALETTE.length];
+      flags = FLAG_FG_EXPLICIT | (next() % 4 === 0 ? FLAG_BOLD : 0);
+    }
+    runLeft--;
+    const base = i * EXPECTED_CELL_SIZE;
+    view.setUint32(base, CHARS.charCodeAt(next() % CHARS.length), true);
+    buffer.set(color, base + 4);
+    view.setUint8(base + 10, flags);
+    view.setUint8(base + 11, 1);
+  }
+  return { cols: COLS, rows: ROWS, buffer };
+}
+
+function percentile(sorted: number[], p: number): number {
+  return sorted[Math.min(sorted.length - 1, Math.floor(sorted.length * p))];
+}
+
+function measure(label: string, glyphCache: boolean, frames: ViewportData[]): string {
+  const stage = document.getElementById("stage")!;
+  stage.innerHTML = "";
+  const canvas = document.createElement("canvas");
+  stage.appendChild(canvas);
+  const renderer = new MiniRenderer(canvas, COLS, ROWS, 14, "monospace", resolveTheme(), { glyphCache });
+  const ctx = canvas.getContext("2d")!;
+
+  const times: number[] = [];
+  for (let i = 0; i < WARMUP + SAMPLES; i++) {
+    const start = performance.now();
+    renderer.render(frames[i % frames.length]);
+    // Read a pixel back so queued GPU work counts towards the frame.
+    ctx.getImageData(0, 0, 1, 1);
+    if (i >= WARMUP) times.push(performance.now() - start);
+  }
+  times.sort((a, b) => a - b);
+  const mean = times.reduce((sum, t) => sum + t, 0) / times.length;
+  return `${label.padEnd(16)} mean ${mean.toFixed(2)}ms  p50 ${percentile(times, 0.5).toFixed(2)}ms  p95 ${percentile(times, 0.95).toFixed(2)}ms`;
+}
+
+const frames = Array.from({ length: FRAMES }, (_, i) => makeFrame(i + 1));
+const results = document.getElementById("results")!;
+results.textContent = [
+  `${COLS}x${ROWS} cells, ${SAMPLES} full redraws, devicePixelRatio ${window.devicePixelRatio}`,
+  measure("glyph atlas", true, frames),
+  measure("batched text", false, frames),
+].join("\n");
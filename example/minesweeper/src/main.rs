@@ -11,6 +11,9 @@
 //!
 //! Run with debug overlay enabled:
 //! `cargo run -p minesweeper-example -- --debug`
+//!
+//! The wasm build keeps its store in a static and exports
+//! `tui_preview_resume`, so tui-preview can save and restore a session.
 
 #[cfg(target_arch = "wasm32")]
 extern crate tinycrossterm as crossterm;
@@ -24,6 +27,8 @@ mod state;
 mod ui;
 
 use std::io;
+#[cfg(target_arch = "wasm32")]
+use std::sync::atomic::{AtomicPtr, Ordering};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crossterm::event;
@@ -159,32 +164,75 @@ fn run_native(debug_enabled: bool) -> io::Result<()> {
     Ok(())
 }
 
+/// Everything the wasm event loop needs, kept on the heap so a restored
+/// copy of linear memory can pick it up again.
 #[cfg(target_arch = "wasm32")]
-fn run_wasm() -> io::Result<()> {
-    use crossterm::event;
+struct WasmSession {
+    store: StoreWithMiddleware<AppState, Action, MinesweeperMiddleware>,
+    cols: u16,
+    rows: u16,
+}
+
+/// Not a `RefCell`: a snapshot taken mid-loop would restore it still borrowed.
+#[cfg(target_arch = "wasm32")]
+static SESSION: AtomicPtr<WasmSession> = AtomicPtr::new(std::ptr::null_mut());
 
-    let (mut cols, mut rows) = crossterm::terminal::size().unwrap_or_else(|_| {
+#[cfg(target_arch = "wasm32")]
+fn run_wasm() -> io::Result<()> {
+    let (cols, rows) = crossterm::terminal::size().unwrap_or_else(|_| {
         let c = std::env::var("COLUMNS").ok().and_then(|s| s.parse().ok()).unwrap_or(80);
         let r = std::env::var("LINES").ok().and_then(|s| s.parse().ok()).unwrap_or(24);
         (c, r)
     });
-    cols = cols.clamp(1, 300);
-    rows = rows.clamp(1, 120);
 
-    let mut store = new_store();
+    let session = Box::leak(Box::new(WasmSession {
+        store: new_store(),
+        cols: cols.clamp(1, 300),
+        rows: rows.clamp(1, 120),
+    }));
+    SESSION.store(session, Ordering::Relaxed);
 
     // Initial render
-    ansi::draw_frame(cols, rows, |frame| {
-        ui::render_in(frame, frame.area(), store.state());
-    })?;
+    draw_wasm(session)?;
+    run_wasm_loop(session)
+}
+
+/// Called by tui-preview instead of `_start` when it restores a saved
+/// session: memory (and so `SESSION`) is the saved copy, the call stack is new.
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn tui_preview_resume() {
+    let session = SESSION.load(Ordering::Relaxed);
+    if session.is_null() {
+        return;
+    }
+    // SAFETY: set once in `run_wasm` from a leaked Box; the saved call stack
+    // that borrowed it is gone.
+    let session = unsafe { &mut *session };
+    if let Err(err) = run_wasm_loop(session) {
+        eprintln!("Error: {err}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn draw_wasm(session: &WasmSession) -> io::Result<()> {
+    ansi::draw_frame(session.cols, session.rows, |frame| {
+        ui::render_in(frame, frame.area(), session.store.state());
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn run_wasm_loop(session: &mut WasmSession) -> io::Result<()> {
+    use crossterm::event;
 
     loop {
         let raw_event = event::read()?;
+        let store = &mut session.store;
 
         match raw_event {
             Event::Resize(new_cols, new_rows) => {
-                cols = new_cols.clamp(1, 300);
-                rows = new_rows.clamp(1, 120);
+                session.cols = new_cols.clamp(1, 300);
+                session.rows = new_rows.clamp(1, 120);
             }
             Event::Key(key) => {
                 if key.kind != KeyEventKind::Press {
@@ -219,12 +267,8 @@ fn run_wasm() -> io::Result<()> {
             _ => continue,
         }
 
-        ansi::draw_frame(cols, rows, |frame| {
-            ui::render_in(frame, frame.area(), store.state());
-        })?;
+        draw_wasm(session)?;
     }
-
-    Ok(())
 }
 
 fn main() -> io::Result<()> {
@@ -0,0 +1,89 @@
+export interface CastControlsState {
+  playing: boolean;
+  time: number;
+  duration: number;
+  speed: number;
+}
+
+const SPEEDS = [0.5, 1, 1.5, 2, 4];
+
+export function CastControls({
+  state,
+  onTogglePlay,
+  onSeek,
+  onSpeed,
+}: {
+  state: CastControlsState;
+  onTogglePlay: () => void;
+  onSeek: (time: number) => void;
+  onSpeed: (speed: number) => void;
+}) {
+  return (
+    <div style={barStyle}>
+      <button
+        type="button"
+        aria-label={state.playing ? "Pause" : "Play"}
+        onClick={onTogglePlay}
+        style={buttonStyle}
+      >
+        {state.playing ? "❚❚" : "▶"}
+      </button>
+      <input
+        type="range"
+        aria-label="Seek"
+        min={0}
+        max={state.duration}
+        step={0.1}
+        value={Math.min(state.time, state.duration)}
+        onChange={(event) => onSeek(Number(event.currentTarget.value))}
+        style={{ flex: 1 }}
+      />
+      <span style={{ fontVariantNumeric: "tabular-nums" }}>
+        {formatTime(state.time)} / {formatTime(state.duration)}
+      </span>
+      <select
+        aria-label="Playback speed"
+        value={state.speed}
+        onChange={(event) => onSpeed(Number(event.currentTarget.value))}
+        style={buttonStyle}
+      >
+        {SPEEDS.map((speed) => (
+          <option key={speed} value={speed}>
+            {speed}×
+          </option>
+        ))}
+      </select>
+    </div>
+  );
+}
+
+function formatTime(seconds: number): string {
+  const whole = Math.max(0, Math.floor(seconds));
+  const minutes = Math.floor(whole / 60);
+  return `${minutes}:${String(whole % 60).padStart(2, "0")}`;
+}
+
+const barStyle: React.CSSProperties = {
+  position: "absolute",
+  left: 0,
+  right: 0,
+  bottom: 0,
+  display: "flex",
+  alignItems: "center",
+  gap: "0.5rem",
+  padding: "0.25rem 0.5rem",
+  fontFamily: "monospace",
+  fontSize: 12,
+  color: "#a9b1d6",
+  background: "rgba(21, 22, 30, 0.85)",
+};
+
+const buttonStyle: React.CSSProperties = {
+  font: "inherit",
+  color: "inherit",
+  background: "transparent",
+  border: "1px solid #414868",
+  borderRadius: 4,
+  padding: "0.1rem 0.4rem",
+  cursor: "pointer",
+};
@@ -0,0 +1,65 @@
+import { useMemo } from "react";
+import { resolveTheme, type StyledSpan } from "./core/libghostty.js";
+import { posterToSpans } from "./core/poster.js";
+import type { GhosttyTheme, TuiPosterSnapshot } from "./types.js";
+
+export interface TuiPosterProps {
+  poster: TuiPosterSnapshot;
+  theme?: Partial<GhosttyTheme>;
+  fontSize?: number;
+  fontFamily?: string;
+  className?: string;
+  style?: React.CSSProperties;
+}
+
+function spanStyle(span: StyledSpan): React.CSSProperties {
+  return {
+    color: span.fg,
+    background: span.bg ?? undefined,
+    fontWeight: span.bold ? "bold" : undefined,
+    fontStyle: span.italic ? "italic" : undefined,
+    textDecoration: span.underline ? "underline" : undefined,
+    opacity: span.faint ? 0.5 : undefined,
+  };
+}
+
+/** Static HTML rendering of a poster snapshot. Renders identically on the server. */
+export function TuiPoster({
+  poster,
+  theme,
+  fontSize = 14,
+  fontFamily = "monospace",
+  className,
+  style,
+}: TuiPosterProps) {
+  const resolvedTheme = useMemo(() => resolveTheme(theme), [theme]);
+  const lines = useMemo(() => posterToSpans(poster, resolvedTheme), [poster, resolvedTheme]);
+
+  return (
+    <pre
+      className={className}
+      aria-hidden="true"
+      style={{
+        margin: 0,
+        fontFamily,
+        fontSize,
+        lineHeight: `${Math.ceil(fontSize) + 2}px`,
+        color: resolvedTheme.foreground,
+        background: resolvedTheme.background,
+        whiteSpace: "pre",
+        overflow: "hidden",
+        ...style,
+      }}
+    >
+      {lines.map((line, y) => (
+        <span key={y} style={{ display: "block", height: `${Math.ceil(fontSize) + 2}px` }}>
+          {line.map((span, x) => (
+            <span key={x} style={spanStyle(span)}>
+              {span.text}
+            </span>
+          ))}
+        </span>
+      ))}
+    </pre>
+  );
+}
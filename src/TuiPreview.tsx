@@ -1,68 +1,322 @@
-import { useEffect, useMemo, useRef, useState } from "react";
-import { createMiniTerminalSurface, measureCellSize } from "./core/libghostty.js";
-import { resolveTuiPreviewProps } from "./core/normalize.js";
-import { WasiBridge, instantiateApp } from "./core/wasi.js";
-import type { TuiPreviewProps, TuiPreviewStatus, TuiRuntimeSize } from "./types.js";
+import { forwardRef, useEffect, useImperativeHandle, useMemo, useReducer, useRef, useState } from "react";
+import {
+  createMiniTerminalSurface,
+  encodeKeys,
+  fontStack,
+  loadTerminalFont,
+  measureCellSize,
+  pickFontOptions,
+  type MiniTerminalSurface,
+} from "./core/libghostty.js";
+import { reduceTermSize, resolveTuiPreviewProps } from "./core/normalize.js";
+import { CastControls, type CastControlsState } from "./CastControls.js";
+import { TuiPoster } from "./TuiPoster.js";
+import { CastPlayer, CastRecorder, loadAsciicast } from "./core/asciicast.js";
+import { viewportToHtml, viewportToSvg, type ScreenExportOptions } from "./core/export.js";
+import { ScriptPlayer, normalizeScriptStep } from "./core/script.js";
+import { serializeScreen } from "./core/serialize.js";
+import { captureSession, restoreSession } from "./core/session.js";
+import { WasiBridge, feedStdin, instantiateApp } from "./core/wasi.js";
+import { createWorkerTerminalSurface, supportsWorkerRendering } from "./core/worker-surface.js";
+import type {
+  TuiOutputCallback,
+  TuiPreviewHandle,
+  TuiPreviewProps,
+  TuiPreviewStatus,
+  TuiSessionSnapshot,
+  TuiStderrTarget,
+} from "./types.js";
 
-export function TuiPreview(props: TuiPreviewProps) {
+/** Cap on the stderr panel backlog, in characters. */
+const STDERR_PANEL_LIMIT = 64 * 1024;
+
+function createRecorder(cols: number, rows: number): CastRecorder {
+  return new CastRecorder({
+    width: cols,
+    height: rows,
+    env: { TERM: "xterm-256color" },
+  });
+}
+
+export const TuiPreview = forwardRef<TuiPreviewHandle, TuiPreviewProps>(function TuiPreview(
+  props,
+  ref
+) {
   const resolved = useMemo(() => resolveTuiPreviewProps(props), [props]);
   const wrapperRef = useRef<HTMLDivElement>(null);
   const containerRef = useRef<HTMLDivElement>(null);
-  const [status, setStatus] = useState<TuiPreviewStatus>("loading");
+  const initialStatus: TuiPreviewStatus = resolved.start === "eager" ? "loading" : "idle";
+  const [status, setStatus] = useState<TuiPreviewStatus>(initialStatus);
+  const statusRef = useRef<TuiPreviewStatus>(initialStatus);
+  const [started, setStarted] = useState(resolved.start === "eager");
+  // Whether the preview is on screen; rendering and stdin are suspended while it isn't.
+  const visibleRef = useRef(true);
+  const bridgeRef = useRef<WasiBridge | null>(null);
+  const surfaceRef = useRef<MiniTerminalSurface | null>(null);
+  const playerRef = useRef<ScriptPlayer | null>(null);
+  const castPlayerRef = useRef<CastPlayer | null>(null);
+  const [castState, setCastState] = useState<CastControlsState | null>(null);
+  // Read when the app (re)starts, so inline script arrays don't restart the app.
+  const scriptRef = useRef(resolved.script);
+  scriptRef.current = resolved.script;
+  const recorderRef = useRef<CastRecorder | null>(null);
+  const recordRef = useRef(resolved.record);
+  recordRef.current = resolved.record;
+  const onRecordingCompleteRef = useRef(resolved.onRecordingComplete);
+  onRecordingCompleteRef.current = resolved.onRecordingComplete;
+  const onTitleChangeRef = useRef(resolved.onTitleChange);
+  onTitleChangeRef.current = resolved.onTitleChange;
+  const onStdoutRef = useRef(resolved.onStdout);
+  onStdoutRef.current = resolved.onStdout;
+  const onStderrRef = useRef(resolved.onStderr);
+  onStderrRef.current = resolved.onStderr;
+  const terminalOptionsRef = useRef(resolved.terminal);
+  terminalOptionsRef.current = resolved.terminal;
+  // Read when the app starts; each session object is restored once, so restart() starts fresh.
+  const sessionRef = useRef(resolved.session);
+  sessionRef.current = resolved.session;
+  const restoredSessionRef = useRef<TuiSessionSnapshot | null>(null);
+  const saveSessionRef = useRef<(() => TuiSessionSnapshot | null) | null>(null);
+  const [restartCount, setRestartCount] = useState(0);
   const [errorMsg, setErrorMsg] = useState("");
+  const [stderrLog, setStderrLog] = useState("");
   const cellSizeRef = useRef<{ w: number; h: number } | null>(null);
-  const [termSize, setTermSize] = useState<TuiRuntimeSize | null>(
-    resolved.fit === "container" ? null : resolved.size
+  // Bumped when the surface's cell size changes under it, so the container fit is redone.
+  const [cellSizeEpoch, setCellSizeEpoch] = useState(0);
+  const [{ size: termSize, fitting }, dispatchTermSize] = useReducer(
+    reduceTermSize,
+    { size: null, fitting: false },
+    (initial) => reduceTermSize(initial, { type: "reset", fit: resolved.fit, size: resolved.size })
   );
 
   useEffect(() => {
-    setTermSize(resolved.fit === "container" ? null : resolved.size);
+    dispatchTermSize({ type: "reset", fit: resolved.fit, size: resolved.size });
   }, [resolved.fit, resolved.size.cols, resolved.size.rows]);
 
+  // Compared by content so an inline array prop doesn't restart the app on every render.
+  const fallbackFontsKey = resolved.terminal.fallbackFonts.join("\n");
+
   useEffect(() => {
-    if (resolved.fit !== "container") return;
+    if (resolved.fit !== "container" || !fitting) return;
     if (!wrapperRef.current) return;
     const wrapper = wrapperRef.current;
-    const estimatedCell = measureCellSize(resolved.terminal.fontSize, resolved.terminal.fontFamily);
+    let cancelled = false;
+    let observer: ResizeObserver | null = null;
+    let estimatedCell = { w: 0, h: 0 };
 
     const updateFromPixels = (width: number, height: number) => {
       const cellW = cellSizeRef.current?.w ?? estimatedCell.w;
       const cellH = cellSizeRef.current?.h ?? estimatedCell.h;
       const newCols = Math.max(1, Math.floor(width / cellW));
       const newRows = Math.max(1, Math.floor(height / cellH));
-      setTermSize((prev) => {
-        if (prev && prev.cols === newCols && prev.rows === newRows) return prev;
-        return { cols: newCols, rows: newRows };
+      dispatchTermSize({ type: "fit", size: { cols: newCols, rows: newRows } });
+    };
+
+    // Measure with the real font so the first grid isn't sized for a fallback.
+    void loadTerminalFont(
+      resolved.terminal.fontSize,
+      resolved.terminal.fontFamily,
+      resolved.terminal.fontLoadTimeout,
+      resolved.terminal
+    ).then(() => {
+      if (cancelled) return;
+      estimatedCell = measureCellSize(
+        resolved.terminal.fontSize,
+        resolved.terminal.fontFamily,
+        resolved.terminal
+      );
+      const rect = wrapper.getBoundingClientRect();
+      if (rect.width > 0 && rect.height > 0) {
+        updateFromPixels(rect.width, rect.height);
+      }
+
+      observer = new ResizeObserver(([entry]) => {
+        const { width, height } = entry.contentRect;
+        if (width > 0 && height > 0) {
+          updateFromPixels(width, height);
+        }
       });
+      observer.observe(wrapper);
+    });
+
+    return () => {
+      cancelled = true;
+      observer?.disconnect();
     };
+  }, [
+    resolved.fit,
+    fitting,
+    resolved.terminal.fontSize,
+    resolved.terminal.fontFamily,
+    resolved.terminal.fontFamilyBold,
+    resolved.terminal.fontFamilyItalic,
+    fallbackFontsKey,
+    resolved.terminal.lineHeight,
+    resolved.terminal.letterSpacing,
+    resolved.terminal.fontLoadTimeout,
+    cellSizeEpoch,
+  ]);
+
+  const finishRecording = () => {
+    const recorder = recorderRef.current;
+    if (!recorder) return null;
+    recorderRef.current = null;
+    const cast = recorder.toString();
+    onRecordingCompleteRef.current?.(cast);
+    return cast;
+  };
+
+  const exportOptions = (surface: MiniTerminalSurface): ScreenExportOptions => ({
+    theme: terminalOptionsRef.current.theme,
+    fontSize: terminalOptionsRef.current.fontSize,
+    fontFamily: fontStack(terminalOptionsRef.current.fontFamily, terminalOptionsRef.current.fallbackFonts),
+    cellWidth: surface.cellSize.w,
+    cellHeight: surface.cellSize.h,
+  });
 
-    const rect = wrapper.getBoundingClientRect();
-    if (rect.width > 0 && rect.height > 0) {
-      updateFromPixels(rect.width, rect.height);
+  // Hand over an in-progress recording when the preview unmounts.
+  useEffect(() => () => void finishRecording(), []);
+
+  useEffect(() => {
+    if (started) return;
+    if (resolved.start === "eager") {
+      setStarted(true);
+      return;
     }
+    if (resolved.start !== "visible" || !wrapperRef.current) return;
+    if (typeof IntersectionObserver === "undefined") {
+      setStarted(true);
+      return;
+    }
+    const observer = new IntersectionObserver(([entry]) => {
+      if (entry.isIntersecting) {
+        observer.disconnect();
+        setStarted(true);
+      }
+    });
+    observer.observe(wrapperRef.current);
+    return () => observer.disconnect();
+  }, [started, resolved.start]);
 
-    const observer = new ResizeObserver(([entry]) => {
-      const { width, height } = entry.contentRect;
-      if (width > 0 && height > 0) {
-        updateFromPixels(width, height);
+  useEffect(() => {
+    if (!started || !wrapperRef.current || typeof IntersectionObserver === "undefined") return;
+    const observer = new IntersectionObserver(([entry]) => {
+      visibleRef.current = entry.isIntersecting;
+      surfaceRef.current?.setSuspended(!entry.isIntersecting);
+      if (entry.isIntersecting) {
+        bridgeRef.current?.resumeInput();
+      } else {
+        bridgeRef.current?.pauseInput();
       }
     });
+    observer.observe(wrapperRef.current);
+    return () => {
+      observer.disconnect();
+      visibleRef.current = true;
+    };
+  }, [started]);
 
-    observer.observe(wrapper);
-    return () => observer.disconnect();
-  }, [resolved.fit, resolved.terminal.fontSize, resolved.terminal.fontFamily]);
+  useImperativeHandle(
+    ref,
+    () => ({
+      sendInput(data) {
+        bridgeRef.current?.pushInput(data);
+      },
+      sendKeys(keys) {
+        const bridge = bridgeRef.current;
+        if (!bridge) return;
+        for (const encoded of encodeKeys(keys)) {
+          bridge.pushInput(encoded);
+        }
+      },
+      restart() {
+        setRestartCount((count) => count + 1);
+      },
+      kill(code) {
+        bridgeRef.current?.kill(code);
+      },
+      focus() {
+        surfaceRef.current?.focus();
+      },
+      resize(cols, rows) {
+        dispatchTermSize({ type: "resize", size: { cols, rows } });
+      },
+      getText() {
+        return surfaceRef.current?.getText() ?? "";
+      },
+      getStatus() {
+        return statusRef.current;
+      },
+      play() {
+        castPlayerRef.current?.play();
+      },
+      pause() {
+        castPlayerRef.current?.pause();
+      },
+      seek(time) {
+        castPlayerRef.current?.seek(time);
+      },
+      setSpeed(speed) {
+        castPlayerRef.current?.setSpeed(speed);
+      },
+      pauseScript() {
+        playerRef.current?.pause();
+      },
+      resumeScript() {
+        playerRef.current?.resume();
+      },
+      stopScript() {
+        playerRef.current?.stop();
+      },
+      startRecording() {
+        if (recorderRef.current) return;
+        const surface = surfaceRef.current;
+        recorderRef.current = createRecorder(surface?.cols ?? 80, surface?.rows ?? 24);
+      },
+      stopRecording() {
+        return finishRecording();
+      },
+      exportSvg() {
+        const surface = surfaceRef.current;
+        return surface ? viewportToSvg(surface.getViewportData(), exportOptions(surface)) : "";
+      },
+      exportHtml() {
+        const surface = surfaceRef.current;
+        return surface ? viewportToHtml(surface.getViewportData(), exportOptions(surface)) : "";
+      },
+      exportAnsi() {
+        const surface = surfaceRef.current;
+        return surface ? serializeScreen(surface.getViewportData(), surface.getState()) : "";
+      },
+      exportPng() {
+        const surface = surfaceRef.current;
+        if (!surface) return Promise.reject(new Error("TuiPreview is not running."));
+        return surface.toPng();
+      },
+      saveSession() {
+        if (statusRef.current !== "running") return null;
+        return saveSessionRef.current?.() ?? null;
+      },
+    }),
+    []
+  );
 
   useEffect(() => {
-    if (!termSize || !containerRef.current) return;
+    if (!termSize || !containerRef.current || !started) return;
 
     let cancelled = false;
     let disposeRenderSurface: (() => void) | null = null;
     let activeBridge: WasiBridge | null = null;
+    let activePlayer: ScriptPlayer | null = null;
+    let activeCastPlayer: CastPlayer | null = null;
+    let castProgressTimer: ReturnType<typeof setInterval> | null = null;
+    const stdinAbort = new AbortController();
 
     const container = containerRef.current;
     const activeSize = termSize;
 
     const setStatusAndNotify = (next: TuiPreviewStatus) => {
+      statusRef.current = next;
       setStatus(next);
       resolved.onStatusChange?.(next);
     };
@@ -75,71 +329,212 @@ export function TuiPreview(props: TuiPreviewProps) {
 
     setStatusAndNotify("loading");
     setErrorMsg("");
+    setStderrLog("");
+    setCastState(null);
 
     async function setup() {
       try {
         let appCols = activeSize.cols;
         let appRows = activeSize.rows;
 
-        const runOnce = async (surface: Awaited<ReturnType<typeof createMiniTerminalSurface>>) => {
+        const runCast = async (surface: MiniTerminalSurface, source: string | URL) => {
+          const cast = await loadAsciicast(source);
+          if (cancelled) return;
+          surface.resize(cast.header.width, cast.header.height);
+
+          const player = new CastPlayer(
+            cast,
+            {
+              write: (text) => surface.write(text),
+              resize: (cols, rows) => surface.resize(cols, rows),
+              reset: () => surface.write("\x1bc"),
+            },
+            {
+              speed: resolved.playback.speed,
+              loop: resolved.playback.loop,
+              idleTimeLimit: resolved.playback.idleTimeLimit,
+              onStateChange: () => syncCastState(player),
+              onEnd: () => {
+                if (!cancelled) setStatusAndNotify("exited");
+              },
+            }
+          );
+          activeCastPlayer = player;
+          castPlayerRef.current = player;
+          syncCastState(player);
+          castProgressTimer = setInterval(() => {
+            if (player.playing) syncCastState(player);
+          }, 250);
+          if (resolved.playback.autoplay) {
+            player.play();
+          }
+        };
+
+        const syncCastState = (player: CastPlayer) => {
+          if (cancelled) return;
+          if (player.playing && statusRef.current !== "running") {
+            setStatusAndNotify("running");
+          }
+          setCastState({
+            playing: player.playing,
+            time: player.currentTime,
+            duration: player.duration,
+            speed: player.speed,
+          });
+        };
+
+        const runOnce = async (surface: MiniTerminalSurface, wasm: string | URL) => {
           const resolvedArgs = resolved.resolveArgv({ cols: appCols, rows: appRows });
           const stdoutDecoder = new TextDecoder();
           const stderrDecoder = new TextDecoder();
+          const inputDecoder = new TextDecoder();
 
           const flushSurfaceOutput = (
             data: Uint8Array,
             decoder: TextDecoder,
-            bridge: WasiBridge
+            target: TuiStderrTarget,
+            callback: TuiOutputCallback | undefined
           ) => {
             const decoded = decoder.decode(data, { stream: true });
-            if (decoded) {
-              surface.write(decoded);
+            callback?.(data, decoded);
+            if (!decoded) return;
+            if (target === "console") {
+              console.error(decoded);
+              return;
             }
-            for (const response of surface.drainResponses()) {
-              bridge.pushInput(response);
+            if (target === "panel") {
+              setStderrLog((prev) => (prev + decoded).slice(-STDERR_PANEL_LIMIT));
+              return;
             }
+            recorderRef.current?.output(decoded);
+            // Replies to queries in `decoded` reach the bridge through onResponse.
+            surface.write(decoded);
+            activePlayer?.notifyOutput();
           };
 
-          let bridge: WasiBridge;
-          bridge = new WasiBridge({
-            args: [resolved.wasm.toString(), ...resolvedArgs],
+          const bridge = new WasiBridge({
+            args: [wasm.toString(), ...resolvedArgs],
             env: {
               COLUMNS: String(appCols),
               LINES: String(appRows),
               ...resolved.env,
             },
-            stdout: (data) => flushSurfaceOutput(data, stdoutDecoder, bridge),
-            stderr: (data) => flushSurfaceOutput(data, stderrDecoder, bridge),
+            determinism: resolved.determinism ?? undefined,
+            stdout: (data) =>
+              flushSurfaceOutput(data, stdoutDecoder, "terminal", onStdoutRef.current),
+            stderr: (data) =>
+              flushSurfaceOutput(data, stderrDecoder, resolved.stderr, onStderrRef.current),
+            onInput: (data) => {
+              recorderRef.current?.input(inputDecoder.decode(data, { stream: true }));
+            },
             onExit: (code) => {
               if (!cancelled) {
                 setStatusAndNotify("exited");
+                finishRecording();
                 resolved.onExit?.(code);
               }
             },
           });
 
+          if (recorderRef.current) {
+            recorderRef.current.resize(appCols, appRows);
+          } else if (recordRef.current) {
+            recorderRef.current = createRecorder(appCols, appRows);
+          }
+
           activeBridge = bridge;
+          bridgeRef.current = bridge;
+          if (!visibleRef.current) {
+            bridge.pauseInput();
+          }
+
+          const session = sessionRef.current;
+          const restoring = session !== null && session !== restoredSessionRef.current;
+
+          // A restored guest already consumed stdin; its saved input queue is used instead.
+          if (!restoring && resolved.stdin !== null) {
+            void feedStdin(bridge, resolved.stdin, {
+              close: resolved.closeStdin,
+              signal: stdinAbort.signal,
+            }).catch((stdinError) => {
+              if (!cancelled) {
+                setError(stdinError);
+              }
+            });
+          } else if (!restoring && resolved.closeStdin) {
+            bridge.closeInput();
+          }
 
-          const wasmApp = await instantiateApp(resolved.wasm, bridge);
+          const wasmApp = restoring
+            ? await restoreSession(session, wasm, bridge, surface, { cols: appCols, rows: appRows })
+            : await instantiateApp(wasm, bridge);
           if (cancelled) return;
+          if (restoring) {
+            restoredSessionRef.current = session;
+          }
+          saveSessionRef.current = () => captureSession(wasm, wasmApp, bridge, surface);
+
+          const script = restoring ? null : scriptRef.current;
+          if (script) {
+            const player = new ScriptPlayer(
+              script.steps.map((step) => normalizeScriptStep(step, script.typingDelay)),
+              {
+                sendInput: (data) => bridge.pushInput(data),
+                getText: () => surface.getText(),
+              }
+            );
+            activePlayer = player;
+            playerRef.current = player;
+            void player.play().then(
+              (result) => {
+                if (result === "done" && script.loop && !cancelled) {
+                  setRestartCount((count) => count + 1);
+                }
+              },
+              (scriptError) => {
+                if (!cancelled) {
+                  resolved.onError?.(scriptError);
+                }
+              }
+            );
+          }
 
           await wasmApp.run();
         };
 
-        const surface = await createMiniTerminalSurface({
+        const createSurface =
+          resolved.terminal.worker && resolved.terminal.renderer === "canvas" && supportsWorkerRendering()
+            ? createWorkerTerminalSurface
+            : createMiniTerminalSurface;
+        const surface = await createSurface({
           container,
           cols: activeSize.cols,
           rows: activeSize.rows,
           fontSize: resolved.terminal.fontSize,
           fontFamily: resolved.terminal.fontFamily,
+          ...pickFontOptions(resolved.terminal),
+          minimumContrastRatio: resolved.terminal.minimumContrastRatio,
+          daltonize: resolved.terminal.daltonize,
           theme: resolved.terminal.theme,
           convertEol: resolved.terminal.convertEol,
           interactive: resolved.mode !== "static" && resolved.interactive,
           showCursor: resolved.mode !== "static",
           wasmUrl: resolved.terminal.wasmUrl,
+          renderer: resolved.terminal.renderer,
+          announce: resolved.terminal.announce,
+          fontLoadTimeout: resolved.terminal.fontLoadTimeout,
+          onCellSizeChange: (cellSize) => {
+            cellSizeRef.current = cellSize;
+            setCellSizeEpoch((epoch) => epoch + 1);
+          },
           onInput: (data) => {
+            if (activePlayer && scriptRef.current?.handOffOnInput) {
+              activePlayer.stop();
+            }
             activeBridge?.pushInput(data);
           },
+          onResponse: (data) => activeBridge?.pushResponse(data),
+          onTitle: (title) => onTitleChangeRef.current?.(title),
         });
 
         if (cancelled) {
@@ -148,15 +543,25 @@ export function TuiPreview(props: TuiPreviewProps) {
         }
 
         disposeRenderSurface = () => surface.dispose();
+        surfaceRef.current = surface;
+        surface.setSuspended(!visibleRef.current);
         appCols = surface.cols;
         appRows = surface.rows;
         cellSizeRef.current = surface.cellSize;
 
         setStatusAndNotify("running");
 
+        const castSource = resolved.cast;
+        const wasmSource = resolved.wasm;
         queueMicrotask(() => {
           if (cancelled) return;
-          void runOnce(surface).catch((runError) => {
+          const run =
+            castSource !== null
+              ? runCast(surface, castSource)
+              : wasmSource !== null
+                ? runOnce(surface, wasmSource)
+                : Promise.reject(new Error("TuiPreview needs either `wasm` or `cast`."));
+          void run.catch((runError) => {
             if (!cancelled) {
               setError(runError);
             }
@@ -173,18 +578,44 @@ export function TuiPreview(props: TuiPreviewProps) {
 
     return () => {
       cancelled = true;
+      stdinAbort.abort();
+      activePlayer?.stop();
+      activePlayer = null;
+      activeCastPlayer?.dispose();
+      activeCastPlayer = null;
+      castPlayerRef.current = null;
+      if (castProgressTimer !== null) {
+        clearInterval(castProgressTimer);
+        castProgressTimer = null;
+      }
+      playerRef.current = null;
+      saveSessionRef.current = null;
       activeBridge = null;
+      bridgeRef.current = null;
+      surfaceRef.current = null;
       disposeRenderSurface?.();
       disposeRenderSurface = null;
     };
   }, [
     resolved.mode,
     termSize,
+    started,
+    restartCount,
     resolved.wasm,
+    resolved.cast,
+    resolved.playback.speed,
+    resolved.playback.loop,
+    resolved.playback.autoplay,
+    resolved.playback.idleTimeLimit,
     resolved.resolveArgv,
     resolved.env,
+    resolved.determinism?.seed,
+    resolved.determinism?.epochMs,
+    resolved.stdin,
+    resolved.closeStdin,
     resolved.fit,
     resolved.interactive,
+    resolved.stderr,
     resolved.onExit,
     resolved.onError,
     resolved.onStatusChange,
@@ -193,8 +624,23 @@ export function TuiPreview(props: TuiPreviewProps) {
     resolved.terminal.theme,
     resolved.terminal.wasmUrl,
     resolved.terminal.convertEol,
+    resolved.terminal.worker,
+    resolved.terminal.renderer,
+    resolved.terminal.announce,
+    resolved.terminal.fontLoadTimeout,
+    resolved.terminal.fontFamilyBold,
+    resolved.terminal.fontFamilyItalic,
+    fallbackFontsKey,
+    resolved.terminal.lineHeight,
+    resolved.terminal.letterSpacing,
+    resolved.terminal.ligatures,
+    resolved.terminal.minimumContrastRatio,
+    resolved.terminal.daltonize,
   ]);
 
+  // The poster stands in for the canvas until the app is actually running.
+  const showPoster = resolved.poster !== null && (status === "idle" || status === "loading");
+
   return (
     <div
       ref={wrapperRef}
@@ -211,14 +657,46 @@ export function TuiPreview(props: TuiPreviewProps) {
       <div
         ref={containerRef}
         style={{
-          display: status === "error" ? "none" : "flex",
+          display: status === "error" || showPoster ? "none" : "flex",
           justifyContent: "center",
           alignItems: "center",
           width: "100%",
           height: "100%",
         }}
       />
-      {status === "loading" && <div style={overlayStyle}>Loading…</div>}
+      {showPoster && resolved.poster && (
+        <TuiPoster
+          poster={resolved.poster}
+          theme={resolved.terminal.theme}
+          fontSize={resolved.terminal.fontSize}
+          fontFamily={resolved.terminal.fontFamily}
+        />
+      )}
+      {resolved.cast !== null && resolved.playback.controls && castState && (
+        <CastControls
+          state={castState}
+          onTogglePlay={() => {
+            const player = castPlayerRef.current;
+            if (!player) return;
+            if (player.playing) player.pause();
+            else player.play();
+          }}
+          onSeek={(time) => castPlayerRef.current?.seek(time)}
+          onSpeed={(speed) => castPlayerRef.current?.setSpeed(speed)}
+        />
+      )}
+      {resolved.stderr === "panel" && stderrLog && (
+        <details style={stderrPanelStyle}>
+          <summary style={{ cursor: "pointer" }}>stderr</summary>
+          <pre style={{ margin: 0, whiteSpace: "pre-wrap" }}>{stderrLog}</pre>
+        </details>
+      )}
+      {status === "idle" && resolved.start === "click" && (
+        <button type="button" style={runButtonStyle} onClick={() => setStarted(true)}>
+          ▶ Run
+        </button>
+      )}
+      {status === "loading" && !showPoster && <div style={overlayStyle}>Loading…</div>}
       {status === "error" && (
         <div style={{ ...overlayStyle, color: "#f7768e" }}>
           Error: {errorMsg}
@@ -226,7 +704,7 @@ export function TuiPreview(props: TuiPreviewProps) {
       )}
     </div>
   );
-}
+});
 
 const overlayStyle: React.CSSProperties = {
   padding: "1rem",
@@ -234,3 +712,34 @@ const overlayStyle: React.CSSProperties = {
   fontSize: 14,
   color: "#a9b1d6",
 };
+
+const runButtonStyle: React.CSSProperties = {
+  position: "absolute",
+  inset: 0,
+  margin: "auto",
+  width: "fit-content",
+  height: "fit-content",
+  padding: "0.5rem 1rem",
+  fontFamily: "monospace",
+  fontSize: 14,
+  color: "#a9b1d6",
+  background: "rgba(21, 22, 30, 0.92)",
+  border: "1px solid #414868",
+  borderRadius: 4,
+  cursor: "pointer",
+};
+
+const stderrPanelStyle: React.CSSProperties = {
+  position: "absolute",
+  left: 0,
+  right: 0,
+  bottom: 0,
+  maxHeight: "40%",
+  overflow: "auto",
+  padding: "0.25rem 0.5rem",
+  fontFamily: "monospace",
+  fontSize: 12,
+  color: "#f7768e",
+  background: "rgba(21, 22, 30, 0.92)",
+  borderTop: "1px solid #414868",
+};
@@ -0,0 +1,342 @@
+/**
+ * asciicast v2 support — parse `.cast` recordings and play them into a
+ * terminal surface with play/pause/seek/speed controls, and record live
+ * sessions back into the same format.
+ *
+ * Format: https://docs.asciinema.org/manual/asciicast/v2/
+ */
+
+import { loadBytes } from "./load.js";
+
+export type AsciicastEventType = "o" | "i" | "r" | "m";
+
+export interface AsciicastHeader {
+  version: 2;
+  width: number;
+  height: number;
+  timestamp?: number;
+  duration?: number;
+  idle_time_limit?: number;
+  command?: string;
+  title?: string;
+  env?: Record<string, string>;
+  [key: string]: unknown;
+}
+
+export interface AsciicastEvent {
+  /** Seconds since the start of the recording */
+  time: number;
+  type: AsciicastEventType;
+  data: string;
+}
+
+export interface Asciicast {
+  header: AsciicastHeader;
+  events: AsciicastEvent[];
+}
+
+/** Parse an asciicast v2 document (newline-delimited JSON). */
+export function parseAsciicast(text: string): Asciicast {
+  const lines = text.split(/\r?\n/);
+  let index = 0;
+  while (index < lines.length && lines[index].trim() === "") index++;
+  if (index >= lines.length) {
+    throw new Error("asciicast is empty.");
+  }
+
+  const header = JSON.parse(lines[index]) as AsciicastHeader;
+  if (header.version !== 2) {
+    throw new Error(`Unsupported asciicast version: ${String(header.version)}`);
+  }
+  if (!(header.width > 0) || !(header.height > 0)) {
+    throw new Error("asciicast header is missing width/height.");
+  }
+
+  const events: AsciicastEvent[] = [];
+  for (let i = index + 1; i < lines.length; i++) {
+    const line = lines[i].trim();
+    if (!line) continue;
+    const parsed = JSON.parse(line) as unknown;
+    if (!Array.isArray(parsed) || parsed.length < 3) {
+      throw new Error(`Invalid asciicast event on line ${i + 1}.`);
+    }
+    const [time, type, data] = parsed as [number, string, string];
+    if (type !== "o" && type !== "i" && type !== "r" && type !== "m") continue;
+    events.push({ time: Number(time), type, data: String(data) });
+  }
+  return { header, events };
+}
+
+/** Serialize a recording to asciicast v2 (newline-delimited JSON). */
+export function serializeAsciicast(cast: Asciicast): string {
+  const lines = [JSON.stringify(cast.header)];
+  for (const event of cast.events) {
+    lines.push(JSON.stringify([Number(event.time.toFixed(6)), event.type, event.data]));
+  }
+  return lines.join("\n") + "\n";
+}
+
+/** Parse an `r` event payload (`"COLSxROWS"`). */
+export function parseResizeEvent(data: string): { cols: number; rows: number } | null {
+  const match = data.match(/^(\d+)x(\d+)$/);
+  if (!match) return null;
+  return { cols: Number(match[1]), rows: Number(match[2]) };
+}
+
+export interface CastPlayerTarget {
+  write(text: string): void;
+  resize(cols: number, rows: number): void;
+  /** Clear the screen and terminal state before replaying from the start. */
+  reset(): void;
+}
+
+export interface CastPlayerOptions {
+  /** Playback speed multiplier. Default: 1 */
+  speed?: number;
+  /** Restart from the beginning when finished. Default: false */
+  loop?: boolean;
+  /** Cap on idle gaps between events, in seconds. Default: header.idle_time_limit */
+  idleTimeLimit?: number;
+  /** Called whenever playing/position changes in a way the UI should reflect */
+  onStateChange?: () => void;
+  /** Called when playback reaches a marker event */
+  onMarker?: (label: string, time: number) => void;
+  /** Called when playback reaches the end (and does not loop) */
+  onEnd?: () => void;
+}
+
+/** Plays asciicast output events into a target in (scaled) real time. */
+export class CastPlayer {
+  private readonly events: AsciicastEvent[];
+  private readonly loop: boolean;
+  private speedValue: number;
+  /** Index of the next event to apply. */
+  private cursor = 0;
+  /** Recording time at the moment playback last (re)started or paused. */
+  private baseTime = 0;
+  /** performance.now() when playback last (re)started. */
+  private startedAt = 0;
+  private isPlaying = false;
+  private timer: ReturnType<typeof setTimeout> | null = null;
+
+  constructor(
+    private readonly cast: Asciicast,
+    private readonly target: CastPlayerTarget,
+    private readonly options: CastPlayerOptions = {}
+  ) {
+    this.events = applyIdleTimeLimit(
+      cast.events,
+      options.idleTimeLimit ?? cast.header.idle_time_limit
+    );
+    this.loop = options.loop ?? false;
+    this.speedValue = options.speed && options.speed > 0 ? options.speed : 1;
+  }
+
+  get duration(): number {
+    const last = this.events[this.events.length - 1];
+    return Math.max(last?.time ?? 0, this.cast.header.duration ?? 0);
+  }
+
+  get currentTime(): number {
+    if (!this.isPlaying) return this.baseTime;
+    const elapsed = ((performance.now() - this.startedAt) / 1000) * this.speedValue;
+    return Math.min(this.duration, this.baseTime + elapsed);
+  }
+
+  get playing(): boolean {
+    return this.isPlaying;
+  }
+
+  get speed(): number {
+    return this.speedValue;
+  }
+
+  /** Markers (`m` events) with their adjusted times, for chapter navigation. */
+  get markers(): Array<{ time: number; label: string }> {
+    return this.events
+      .filter((event) => event.type === "m")
+      .map((event) => ({ time: event.time, label: event.data }));
+  }
+
+  play() {
+    if (this.isPlaying) return;
+    if (this.cursor >= this.events.length && this.baseTime >= this.duration) {
+      this.seek(0);
+    }
+    this.isPlaying = true;
+    this.startedAt = performance.now();
+    this.options.onStateChange?.();
+    this.tick();
+  }
+
+  pause() {
+    if (!this.isPlaying) return;
+    this.baseTime = this.currentTime;
+    this.isPlaying = false;
+    this.clearTimer();
+    this.options.onStateChange?.();
+  }
+
+  setSpeed(speed: number) {
+    if (!(speed > 0)) return;
+    this.baseTime = this.currentTime;
+    this.startedAt = performance.now();
+    this.speedValue = speed;
+    if (this.isPlaying) {
+      this.clearTimer();
+      this.tick();
+    }
+    this.options.onStateChange?.();
+  }
+
+  /** Jump to `time` seconds. Seeking backwards replays output from the start. */
+  seek(time: number) {
+    const target = Math.max(0, Math.min(time, this.duration));
+    if (this.cursor > 0 && (target < this.appliedTime() || target === 0)) {
+      this.target.reset();
+      this.cursor = 0;
+      this.target.resize(this.cast.header.width, this.cast.header.height);
+    }
+    this.applyUntil(target, false);
+    this.baseTime = target;
+    this.startedAt = performance.now();
+    if (this.isPlaying) {
+      this.clearTimer();
+      this.tick();
+    }
+    this.options.onStateChange?.();
+  }
+
+  dispose() {
+    this.isPlaying = false;
+    this.clearTimer();
+  }
+
+  /** Time of the last applied event, or 0 if none. */
+  private appliedTime(): number {
+    return this.cursor > 0 ? this.events[this.cursor - 1].time : 0;
+  }
+
+  private applyUntil(time: number, emitMarkers: boolean) {
+    let output = "";
+    while (this.cursor < this.events.length && this.events[this.cursor].time <= time) {
+      const event = this.events[this.cursor++];
+      if (event.type === "o") {
+        output += event.data;
+      } else if (event.type === "r") {
+        const size = parseResizeEvent(event.data);
+        if (size) {
+          if (output) this.target.write(output);
+          output = "";
+          this.target.resize(size.cols, size.rows);
+        }
+      } else if (event.type === "m" && emitMarkers) {
+        this.options.onMarker?.(event.data, event.time);
+      }
+    }
+    if (output) this.target.write(output);
+  }
+
+  private tick() {
+    this.timer = null;
+    if (!this.isPlaying) return;
+    const now = this.currentTime;
+    this.applyUntil(now, true);
+
+    if (this.cursor >= this.events.length) {
+      if (now < this.duration) {
+        this.schedule(this.duration - now);
+        return;
+      }
+      if (this.loop && this.duration > 0) {
+        this.seek(0);
+        return;
+      }
+      this.baseTime = this.duration;
+      this.isPlaying = false;
+      this.options.onStateChange?.();
+      this.options.onEnd?.();
+      return;
+    }
+    this.schedule(this.events[this.cursor].time - now);
+  }
+
+  private schedule(recordingSeconds: number) {
+    const delayMs = Math.max(0, (recordingSeconds / this.speedValue) * 1000);
+    this.timer = setTimeout(() => this.tick(), delayMs);
+  }
+
+  private clearTimer() {
+    if (this.timer !== null) {
+      clearTimeout(this.timer);
+      this.timer = null;
+    }
+  }
+}
+
+/** Compress gaps longer than `limit` seconds, returning events with adjusted times. */
+function applyIdleTimeLimit(events: AsciicastEvent[], limit: number | undefined): AsciicastEvent[] {
+  if (!limit || limit <= 0) return events;
+  let previous = 0;
+  let shifted = 0;
+  return events.map((event) => {
+    const gap = event.time - previous;
+    previous = event.time;
+    shifted += Math.min(Math.max(0, gap), limit);
+    return { ...event, time: shifted };
+  });
+}
+
+/** Load and parse an asciicast v2 recording: URL, or file path under Node. */
+export async function loadAsciicast(source: string | URL): Promise<Asciicast> {
+  return parseAsciicast(new TextDecoder().decode(await loadBytes(source, "asciicast")));
+}
+
+/** Collects timestamped output, input and resize events from a live session. */
+export class CastRecorder {
+  private readonly header: AsciicastHeader;
+  private readonly events: AsciicastEvent[] = [];
+  private readonly startedAt: number;
+  private cols: number;
+  private rows: number;
+
+  constructor(
+    header: Omit<AsciicastHeader, "version">,
+    private readonly now: () => number = () => performance.now()
+  ) {
+    this.header = { version: 2, timestamp: Math.floor(Date.now() / 1000), ...header };
+    this.cols = header.width;
+    this.rows = header.height;
+    this.startedAt = now();
+  }
+
+  /** Record app output (`o`). */
+  output(text: string) {
+    if (text) this.push("o", text);
+  }
+
+  /** Record input sent to the app (`i`). */
+  input(text: string) {
+    if (text) this.push("i", text);
+  }
+
+  /** Record a terminal resize (`r`); repeated sizes are ignored. */
+  resize(cols: number, rows: number) {
+    if (cols === this.cols && rows === this.rows) return;
+    this.cols = cols;
+    this.rows = rows;
+    this.push("r", `${cols}x${rows}`);
+  }
+
+  toAsciicast(): Asciicast {
+    return { header: { ...this.header }, events: this.events.slice() };
+  }
+
+  toString(): string {
+    return serializeAsciicast(this.toAsciicast());
+  }
+
+  private push(type: AsciicastEventType, data: string) {
+    this.events.push({ time: (this.now() - this.startedAt) / 1000, type, data });
+  }
+}
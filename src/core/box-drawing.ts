@@ -0,0 +1,465 @@
+/**
+ * Procedural box drawing, block elements, braille and powerline separators.
+ * Font glyphs for these rarely fill the cell exactly, leaving seams between
+ * cells; drawing them as device-pixel-aligned rectangles and paths makes
+ * neighbouring cells meet at any font size and devicePixelRatio.
+ */
+
+type DrawContext = Pick<
+  CanvasRenderingContext2D,
+  | "fillRect"
+  | "fillStyle"
+  | "strokeStyle"
+  | "lineWidth"
+  | "lineCap"
+  | "globalAlpha"
+  | "beginPath"
+  | "moveTo"
+  | "lineTo"
+  | "arcTo"
+  | "ellipse"
+  | "closePath"
+  | "fill"
+  | "stroke"
+  | "rect"
+  | "clip"
+  | "save"
+  | "restore"
+>;
+
+const NONE = 0;
+const LIGHT = 1;
+const HEAVY = 2;
+const DOUBLE = 3;
+
+/**
+ * Arm weights for U+2500–U+257F as four digits: up, right, down, left.
+ * Dashed lines, arcs and diagonals are handled separately and left empty.
+ */
+const BOX_ARMS: readonly string[] = [
+  // 2500
+  "0101", "0202", "1010", "2020", "", "", "", "",
+  // 2508
+  "", "", "", "", "0110", "0210", "0120", "0220",
+  // 2510
+  "0011", "0012", "0021", "0022", "1100", "1200", "2100", "2200",
+  // 2518
+  "1001", "1002", "2001", "2002", "1110", "1210", "2110", "1120",
+  // 2520
+  "2120", "2210", "1220", "2220", "1011", "1012", "2011", "1021",
+  // 2528
+  "2021", "2012", "1022", "2022", "0111", "0112", "0211", "0212",
+  // 2530
+  "0121", "0122", "0221", "0222", "1101", "1102", "1201", "1202",
+  // 2538
+  "2101", "2102", "2201", "2202", "1111", "1112", "1211", "1212",
+  // 2540
+  "2111", "1121", "2121", "2112", "2211", "1122", "1221", "2212",
+  // 2548
+  "1222", "2122", "2221", "2222", "", "", "", "",
+  // 2550
+  "0303", "3030", "0310", "0130", "0330", "0013", "0031", "0033",
+  // 2558
+  "1300", "3100", "3300", "1003", "3001", "3003", "1310", "3130",
+  // 2560
+  "3330", "1013", "3031", "3033", "0313", "0131", "0333", "1303",
+  // 2568
+  "3101", "3303", "1313", "3131", "3333", "", "", "",
+  // 2570
+  "", "", "", "", "0001", "1000", "0100", "0010",
+  // 2578
+  "0002", "2000", "0200", "0020", "0201", "1020", "0102", "2010",
+];
+
+/** Dashed lines: codepoint → [dash count, weight, vertical]. */
+const DASHES = new Map<number, [number, number, boolean]>([
+  [0x2504, [3, LIGHT, false]],
+  [0x2505, [3, HEAVY, false]],
+  [0x2506, [3, LIGHT, true]],
+  [0x2507, [3, HEAVY, true]],
+  [0x2508, [4, LIGHT, false]],
+  [0x2509, [4, HEAVY, false]],
+  [0x250a, [4, LIGHT, true]],
+  [0x250b, [4, HEAVY, true]],
+  [0x254c, [2, LIGHT, false]],
+  [0x254d, [2, HEAVY, false]],
+  [0x254e, [2, LIGHT, true]],
+  [0x254f, [2, HEAVY, true]],
+]);
+
+/** Quadrant blocks U+2596–U+259F: bits 1 upper left, 2 upper right, 4 lower left, 8 lower right. */
+const QUADRANTS = [4, 8, 1, 13, 9, 7, 11, 2, 6, 14];
+
+const UP = 0;
+const RIGHT = 1;
+const DOWN = 2;
+const LEFT = 3;
+
+/** The cell in device pixels; edges are rounded the same way for every cell, so neighbours share them. */
+interface DeviceBox {
+  x0: number;
+  y0: number;
+  x1: number;
+  y1: number;
+  /** Light stroke thickness. */
+  light: number;
+  dpr: number;
+}
+
+/** Whether `drawBoxGlyph` draws this codepoint itself. */
+export function isBoxGlyph(codepoint: number): boolean {
+  return (
+    (codepoint >= 0x2500 && codepoint <= 0x259f) ||
+    (codepoint >= 0x2800 && codepoint <= 0x28ff) ||
+    (codepoint >= 0xe0b0 && codepoint <= 0xe0bf)
+  );
+}
+
+/**
+ * Draw `codepoint` into the cell at (`x`, `y`) of size `w`×`h` CSS pixels,
+ * on a context scaled by `dpr`. Returns false for codepoints it doesn't
+ * handle, so the caller falls back to the font.
+ */
+export function drawBoxGlyph(
+  ctx: DrawContext,
+  codepoint: number,
+  x: number,
+  y: number,
+  w: number,
+  h: number,
+  dpr: number,
+  color: string
+): boolean {
+  if (!isBoxGlyph(codepoint)) return false;
+  const x0 = Math.round(x * dpr);
+  const y0 = Math.round(y * dpr);
+  const x1 = Math.round((x + w) * dpr);
+  const y1 = Math.round((y + h) * dpr);
+  const box: DeviceBox = { x0, y0, x1, y1, light: Math.max(1, Math.round((x1 - x0) / 8)), dpr };
+
+  ctx.save();
+  ctx.fillStyle = color;
+  ctx.strokeStyle = color;
+  if (codepoint <= 0x257f) {
+    drawBoxLines(ctx, box, codepoint);
+  } else if (codepoint <= 0x259f) {
+    drawBlock(ctx, box, codepoint);
+  } else if (codepoint <= 0x28ff) {
+    drawBraille(ctx, box, codepoint - 0x2800);
+  } else {
+    drawPowerline(ctx, box, codepoint);
+  }
+  ctx.restore();
+  return true;
+}
+
+/** Fill a rectangle given in device pixels. */
+function fillDevice(ctx: DrawContext, box: DeviceBox, left: number, top: number, right: number, bottom: number) {
+  if (right <= left || bottom <= top) return;
+  ctx.fillRect(left / box.dpr, top / box.dpr, (right - left) / box.dpr, (bottom - top) / box.dpr);
+}
+
+function thickness(box: DeviceBox, weight: number): number {
+  return weight === HEAVY ? box.light * 2 : box.light;
+}
+
+/** Center of the cell, rounded down so every cell's strokes sit at the same offset. */
+function center(box: DeviceBox) {
+  return {
+    cx: box.x0 + Math.floor((box.x1 - box.x0) / 2),
+    cy: box.y0 + Math.floor((box.y1 - box.y0) / 2),
+  };
+}
+
+/**
+ * Where a line of `weight` lies across its direction, relative to the
+ * center: [first pixel, one past the last]. A light line is the reference;
+ * a double line is two light strokes with a light line's gap between them.
+ */
+function extent(box: DeviceBox, weight: number): [number, number] {
+  const light = box.light;
+  const offset = Math.floor(light / 2);
+  if (weight === HEAVY) return [-light, light];
+  if (weight === DOUBLE) return [-offset - light, -offset + 2 * light];
+  return [-offset, -offset + light];
+}
+
+function drawBoxLines(ctx: DrawContext, box: DeviceBox, codepoint: number) {
+  const dash = DASHES.get(codepoint);
+  if (dash) {
+    drawDashes(ctx, box, ...dash);
+    return;
+  }
+  if (codepoint >= 0x256d && codepoint <= 0x2570) {
+    drawArc(ctx, box, codepoint);
+    return;
+  }
+  if (codepoint >= 0x2571 && codepoint <= 0x2573) {
+    drawDiagonals(ctx, box, codepoint);
+    return;
+  }
+  const spec = BOX_ARMS[codepoint - 0x2500];
+  if (!spec) return;
+  const arms = [...spec].map(Number);
+  for (let direction = UP; direction <= LEFT; direction++) {
+    if (arms[direction] !== NONE) drawArm(ctx, box, arms, direction);
+  }
+}
+
+/**
+ * One arm, from the cell edge to the center. Arms run past the center to
+ * cover the perpendicular arms, so corners are square; the strokes of a
+ * double arm stop at a perpendicular double line on their side, which keeps
+ * double corners and junctions open.
+ */
+function drawArm(ctx: DrawContext, box: DeviceBox, arms: number[], direction: number) {
+  const { cx, cy } = center(box);
+  const vertical = direction === UP || direction === DOWN;
+  // Down and right arms run from the center to the far edge; up and left from the near edge to the center.
+  const outward = direction === DOWN || direction === RIGHT;
+  const weight = arms[direction];
+  const opposite = arms[(direction + 2) % 4];
+  // Perpendicular arms, on the side of the first and of the second stroke of a double arm.
+  const first = vertical ? arms[LEFT] : arms[UP];
+  const second = vertical ? arms[RIGHT] : arms[DOWN];
+  const light = box.light;
+  const offset = Math.floor(light / 2);
+  // Meeting points at a perpendicular double line: the near side of its
+  // stroke closer to this arm, or the far side of the other stroke.
+  const nearStroke = outward ? -offset + light : -offset;
+  const farStroke = outward ? -offset - light : -offset + 2 * light;
+
+  // Where the arm meets the center, relative to it.
+  const meet = (near: number, far: number): number => {
+    if (weight === DOUBLE) {
+      if (near === DOUBLE) return nearStroke;
+      if (far === DOUBLE) return farStroke;
+    } else if (first === DOUBLE && second === DOUBLE && opposite === NONE) {
+      // ╟ ╢ ╤ ╧: attach to the near stroke without crossing the gap.
+      return nearStroke;
+    }
+    const covered = [first, second].filter((arm) => arm !== NONE);
+    const extents = (covered.length > 0 ? covered : [weight]).map((arm) => extent(box, arm));
+    return outward ? Math.min(...extents.map(([lo]) => lo)) : Math.max(...extents.map(([, hi]) => hi));
+  };
+
+  // Strokes across the arm, as [first pixel relative to the center line, thickness, meeting point].
+  const [lo, hi] = extent(box, weight);
+  const strokes: [number, number, number][] =
+    weight === DOUBLE
+      ? [
+          [lo, light, meet(first, second)],
+          [-offset + light, light, meet(second, first)],
+        ]
+      : [[lo, hi - lo, meet(first, second)]];
+
+  for (const [across, size, along] of strokes) {
+    if (vertical) {
+      const left = cx + across;
+      if (outward) fillDevice(ctx, box, left, cy + along, left + size, box.y1);
+      else fillDevice(ctx, box, left, box.y0, left + size, cy + along);
+    } else {
+      const top = cy + across;
+      if (outward) fillDevice(ctx, box, cx + along, top, box.x1, top + size);
+      else fillDevice(ctx, box, box.x0, top, cx + along, top + size);
+    }
+  }
+}
+
+function drawDashes(ctx: DrawContext, box: DeviceBox, count: number, weight: number, vertical: boolean) {
+  const { cx, cy } = center(box);
+  const size = thickness(box, weight);
+  const start = vertical ? box.y0 : box.x0;
+  const length = (vertical ? box.y1 : box.x1) - start;
+  for (let i = 0; i < count; i++) {
+    // Each dash is centered in its share of the cell, so dashes repeat evenly across cells.
+    const from = start + Math.round((length * (i + 0.2)) / count);
+    const to = start + Math.round((length * (i + 0.8)) / count);
+    if (vertical) fillDevice(ctx, box, cx - Math.floor(size / 2), from, cx - Math.floor(size / 2) + size, to);
+    else fillDevice(ctx, box, from, cy - Math.floor(size / 2), to, cy - Math.floor(size / 2) + size);
+  }
+}
+
+/** ╭ ╮ ╯ ╰: straight in from both edges, joined by a quarter circle. */
+function drawArc(ctx: DrawContext, box: DeviceBox, codepoint: number) {
+  const { cx, cy } = center(box);
+  const light = box.light;
+  // Stroke along the middle of the light line.
+  const mx = (cx - Math.floor(light / 2) + light / 2) / box.dpr;
+  const my = (cy - Math.floor(light / 2) + light / 2) / box.dpr;
+  const left = box.x0 / box.dpr;
+  const right = box.x1 / box.dpr;
+  const top = box.y0 / box.dpr;
+  const bottom = box.y1 / box.dpr;
+  const radius = Math.min(right - mx, mx - left, bottom - my, my - top);
+  const vertical = codepoint === 0x256d || codepoint === 0x256e ? bottom : top;
+  const horizontal = codepoint === 0x256d || codepoint === 0x2570 ? right : left;
+
+  ctx.lineWidth = light / box.dpr;
+  ctx.lineCap = "butt";
+  ctx.beginPath();
+  ctx.moveTo(mx, vertical);
+  ctx.arcTo(mx, my, horizontal, my, radius);
+  ctx.lineTo(horizontal, my);
+  ctx.stroke();
+}
+
+/** ╱ ╲ ╳, clipped to the cell so the line ends don't bleed into neighbours. */
+function drawDiagonals(ctx: DrawContext, box: DeviceBox, codepoint: number) {
+  const left = box.x0 / box.dpr;
+  const right = box.x1 / box.dpr;
+  const top = box.y0 / box.dpr;
+  const bottom = box.y1 / box.dpr;
+  ctx.beginPath();
+  ctx.rect(left, top, right - left, bottom - top);
+  ctx.clip();
+  ctx.lineWidth = box.light / box.dpr;
+  ctx.beginPath();
+  if (codepoint !== 0x2572) {
+    ctx.moveTo(right, top);
+    ctx.lineTo(left, bottom);
+  }
+  if (codepoint !== 0x2571) {
+    ctx.moveTo(left, top);
+    ctx.lineTo(right, bottom);
+  }
+  ctx.stroke();
+}
+
+function drawBlock(ctx: DrawContext, box: DeviceBox, codepoint: number) {
+  const { x0, y0, x1, y1 } = box;
+  const atX = (fraction: number) => x0 + Math.round((x1 - x0) * fraction);
+  const atY = (fraction: number) => y0 + Math.round((y1 - y0) * fraction);
+
+  if (codepoint === 0x2580) {
+    fillDevice(ctx, box, x0, y0, x1, atY(1 / 2));
+  } else if (codepoint <= 0x2588) {
+    // ▁ ▂ ▃ ▄ ▅ ▆ ▇ █: lower eighths.
+    fillDevice(ctx, box, x0, atY(1 - (codepoint - 0x2580) / 8), x1, y1);
+  } else if (codepoint <= 0x258f) {
+    // ▉ ▊ ▋ ▌ ▍ ▎ ▏: left eighths, widest first.
+    fillDevice(ctx, box, x0, y0, atX((0x2590 - codepoint) / 8), y1);
+  } else if (codepoint === 0x2590) {
+    fillDevice(ctx, box, atX(1 / 2), y0, x1, y1);
+  } else if (codepoint <= 0x2593) {
+    // ░ ▒ ▓ as a translucent fill rather than a dither pattern.
+    ctx.globalAlpha *= (codepoint - 0x2590) / 4;
+    fillDevice(ctx, box, x0, y0, x1, y1);
+  } else if (codepoint === 0x2594) {
+    fillDevice(ctx, box, x0, y0, x1, atY(1 / 8));
+  } else if (codepoint === 0x2595) {
+    fillDevice(ctx, box, atX(7 / 8), y0, x1, y1);
+  } else {
+    const quadrants = QUADRANTS[codepoint - 0x2596];
+    const mx = atX(1 / 2);
+    const my = atY(1 / 2);
+    if (quadrants & 1) fillDevice(ctx, box, x0, y0, mx, my);
+    if (quadrants & 2) fillDevice(ctx, box, mx, y0, x1, my);
+    if (quadrants & 4) fillDevice(ctx, box, x0, my, mx, y1);
+    if (quadrants & 8) fillDevice(ctx, box, mx, my, x1, y1);
+  }
+}
+
+/** Braille dot bits, in Unicode order: dots 1–3 and 7 down the left column, 4–6 and 8 down the right. */
+const BRAILLE_DOTS: readonly [column: number, row: number][] = [
+  [0, 0],
+  [0, 1],
+  [0, 2],
+  [1, 0],
+  [1, 1],
+  [1, 2],
+  [0, 3],
+  [1, 3],
+];
+
+function drawBraille(ctx: DrawContext, box: DeviceBox, pattern: number) {
+  const width = box.x1 - box.x0;
+  const height = box.y1 - box.y0;
+  const size = Math.max(1, Math.round(Math.min(width / 4, height / 8)));
+  for (let bit = 0; bit < 8; bit++) {
+    if (!(pattern & (1 << bit))) continue;
+    const [column, row] = BRAILLE_DOTS[bit];
+    const left = box.x0 + Math.round((width * (column * 2 + 1)) / 4 - size / 2);
+    const top = box.y0 + Math.round((height * (row * 2 + 1)) / 8 - size / 2);
+    fillDevice(ctx, box, left, top, left + size, top + size);
+  }
+}
+
+/**
+ * Powerline separators U+E0B0–U+E0BF: solid and outlined arrows and half
+ * circles, then the corner triangles and the slashes between them.
+ */
+function drawPowerline(ctx: DrawContext, box: DeviceBox, codepoint: number) {
+  const left = box.x0 / box.dpr;
+  const right = box.x1 / box.dpr;
+  const top = box.y0 / box.dpr;
+  const bottom = box.y1 / box.dpr;
+  const middle = (top + bottom) / 2;
+  // Even codepoints are the filled shapes; odd ones are outlines and slashes.
+  const solid = codepoint % 2 === 0;
+  ctx.lineWidth = box.light / box.dpr;
+  ctx.beginPath();
+  ctx.rect(left, top, right - left, bottom - top);
+  ctx.clip();
+  ctx.beginPath();
+
+  switch (codepoint) {
+    case 0xe0b0:
+    case 0xe0b1:
+      ctx.moveTo(left, top);
+      ctx.lineTo(right, middle);
+      ctx.lineTo(left, bottom);
+      break;
+    case 0xe0b2:
+    case 0xe0b3:
+      ctx.moveTo(right, top);
+      ctx.lineTo(left, middle);
+      ctx.lineTo(right, bottom);
+      break;
+    case 0xe0b4:
+    case 0xe0b5:
+      ctx.moveTo(left, top);
+      ctx.ellipse(left, middle, right - left, middle - top, 0, -Math.PI / 2, Math.PI / 2);
+      break;
+    case 0xe0b6:
+    case 0xe0b7:
+      ctx.moveTo(right, top);
+      ctx.ellipse(right, middle, right - left, middle - top, 0, -Math.PI / 2, Math.PI / 2, true);
+      break;
+    case 0xe0b8:
+      triangle(ctx, [left, top], [left, bottom], [right, bottom]);
+      break;
+    case 0xe0ba:
+      triangle(ctx, [right, top], [right, bottom], [left, bottom]);
+      break;
+    case 0xe0bc:
+      triangle(ctx, [left, top], [right, top], [left, bottom]);
+      break;
+    case 0xe0be:
+      triangle(ctx, [left, top], [right, top], [right, bottom]);
+      break;
+    case 0xe0b9:
+    case 0xe0bf:
+      ctx.moveTo(left, top);
+      ctx.lineTo(right, bottom);
+      break;
+    case 0xe0bb:
+    case 0xe0bd:
+      ctx.moveTo(right, top);
+      ctx.lineTo(left, bottom);
+      break;
+  }
+
+  if (solid) {
+    ctx.closePath();
+    ctx.fill();
+  } else {
+    ctx.stroke();
+  }
+}
+
+function triangle(ctx: DrawContext, a: [number, number], b: [number, number], c: [number, number]) {
+  ctx.moveTo(...a);
+  ctx.lineTo(...b);
+  ctx.lineTo(...c);
+}
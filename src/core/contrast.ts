@@ -0,0 +1,168 @@
+/**
+ * Color adjustments applied while painting: a minimum contrast ratio between
+ * each cell's foreground and background, and daltonization filters that
+ * shift colors a color-vision deficiency would confuse into ones it won't.
+ */
+
+import type { TuiDaltonizeMode } from "../types.js";
+import { parseColorToHex, type RgbColor } from "./libghostty.js";
+
+export interface TerminalColorOptions {
+  /** WCAG contrast ratio (1–21) each foreground is pushed to against its background. Default: 1 (off) */
+  minimumContrastRatio?: number;
+  /** Recolor for a color-vision deficiency. Default: "none" */
+  daltonize?: TuiDaltonizeMode;
+}
+
+/** Copy just the color settings, e.g. to post them to a worker. */
+export function pickColorOptions(options: TerminalColorOptions): TerminalColorOptions {
+  return { minimumContrastRatio: options.minimumContrastRatio, daltonize: options.daltonize };
+}
+
+/** Cached adjustments are dropped past this many distinct colors. */
+const CACHE_LIMIT = 4096;
+
+const WHITE: RgbColor = { r: 255, g: 255, b: 255 };
+const BLACK: RgbColor = { r: 0, g: 0, b: 0 };
+
+function channelLuminance(value: number): number {
+  const c = value / 255;
+  return c <= 0.03928 ? c / 12.92 : ((c + 0.055) / 1.055) ** 2.4;
+}
+
+/** WCAG relative luminance, 0 for black to 1 for white. */
+export function relativeLuminance(color: RgbColor): number {
+  return 0.2126 * channelLuminance(color.r) + 0.7152 * channelLuminance(color.g) + 0.0722 * channelLuminance(color.b);
+}
+
+/** WCAG contrast ratio between two colors, from 1 (identical) to 21 (black on white). */
+export function contrastRatio(a: RgbColor, b: RgbColor): number {
+  const la = relativeLuminance(a);
+  const lb = relativeLuminance(b);
+  return (Math.max(la, lb) + 0.05) / (Math.min(la, lb) + 0.05);
+}
+
+function mix(from: RgbColor, to: RgbColor, amount: number): RgbColor {
+  return {
+    r: Math.round(from.r + (to.r - from.r) * amount),
+    g: Math.round(from.g + (to.g - from.g) * amount),
+    b: Math.round(from.b + (to.b - from.b) * amount),
+  };
+}
+
+/**
+ * `fg`, lightened or darkened just enough to reach `minimum` contrast
+ * against `bg`. It moves away from the background first (lighter on a dark
+ * background) and only flips direction when that can't get there; when
+ * neither can, the higher-contrast extreme wins.
+ */
+export function ensureContrast(fg: RgbColor, bg: RgbColor, minimum: number): RgbColor {
+  if (contrastRatio(fg, bg) >= minimum) return fg;
+  const targets = relativeLuminance(fg) >= relativeLuminance(bg) ? [WHITE, BLACK] : [BLACK, WHITE];
+  for (const target of targets) {
+    if (contrastRatio(target, bg) < minimum) continue;
+    let low = 0;
+    let high = 1;
+    for (let i = 0; i < 12; i++) {
+      const middle = (low + high) / 2;
+      if (contrastRatio(mix(fg, target, middle), bg) >= minimum) high = middle;
+      else low = middle;
+    }
+    return mix(fg, target, high);
+  }
+  return contrastRatio(WHITE, bg) >= contrastRatio(BLACK, bg) ? WHITE : BLACK;
+}
+
+type Matrix = readonly [number, number, number, number, number, number, number, number, number];
+
+function apply(m: Matrix, [a, b, c]: readonly [number, number, number]): [number, number, number] {
+  return [m[0] * a + m[1] * b + m[2] * c, m[3] * a + m[4] * b + m[5] * c, m[6] * a + m[7] * b + m[8] * c];
+}
+
+// prettier-ignore
+const RGB_TO_LMS: Matrix = [
+  17.8824, 43.5161, 4.11935,
+  3.45565, 27.1554, 3.86714,
+  0.0299566, 0.184309, 1.46709,
+];
+// prettier-ignore
+const LMS_TO_RGB: Matrix = [
+  0.0809444479, -0.130504409, 0.116721066,
+  -0.0102485335, 0.0540193266, -0.113614708,
+  -0.000365296938, -0.00412161469, 0.693511405,
+];
+/** What each deficiency sees, in LMS space. */
+const SIMULATIONS: Record<Exclude<TuiDaltonizeMode, "none">, Matrix> = {
+  protanopia: [0, 2.02344, -2.52581, 0, 1, 0, 0, 0, 1],
+  deuteranopia: [1, 0, 0, 0.494207, 0, 1.24827, 0, 0, 1],
+  tritanopia: [1, 0, 0, 0, 1, 0, -0.395913, 0.801109, 0],
+};
+/** Moves the lost information into channels that are still seen. */
+const ERROR_SHIFT: Matrix = [0, 0, 0, 0.7, 1, 0, 0.7, 0, 1];
+
+const clampChannel = (value: number) => Math.min(255, Math.max(0, Math.round(value)));
+
+/**
+ * Daltonize a color: simulate the deficiency, and add the difference it
+ * can't see back into the channels it can.
+ */
+export function daltonize(color: RgbColor, mode: TuiDaltonizeMode): RgbColor {
+  if (mode === "none") return color;
+  const rgb = [color.r, color.g, color.b] as const;
+  const seen = apply(LMS_TO_RGB, apply(SIMULATIONS[mode], apply(RGB_TO_LMS, rgb)));
+  const shift = apply(ERROR_SHIFT, [rgb[0] - seen[0], rgb[1] - seen[1], rgb[2] - seen[2]]);
+  return {
+    r: clampChannel(rgb[0] + shift[0]),
+    g: clampChannel(rgb[1] + shift[1]),
+    b: clampChannel(rgb[2] + shift[2]),
+  };
+}
+
+function toRgb(css: string): RgbColor {
+  const hex = parseColorToHex(css);
+  return { r: (hex >> 16) & 0xff, g: (hex >> 8) & 0xff, b: hex & 0xff };
+}
+
+function toCss(color: RgbColor): string {
+  return `rgb(${color.r}, ${color.g}, ${color.b})`;
+}
+
+/** Applies `TerminalColorOptions` to the CSS colors a renderer is about to paint, caching the results. */
+export class ColorAdjuster {
+  /** False when there is nothing to adjust; renderers can skip the lookups. */
+  readonly active: boolean;
+  private readonly minimumContrastRatio: number;
+  private readonly mode: TuiDaltonizeMode;
+  private readonly cache = new Map<string, string>();
+
+  constructor(options: TerminalColorOptions = {}) {
+    this.minimumContrastRatio = Math.min(21, Math.max(1, options.minimumContrastRatio ?? 1));
+    this.mode = options.daltonize ?? "none";
+    this.active = this.minimumContrastRatio > 1 || this.mode !== "none";
+  }
+
+  background(css: string): string {
+    if (this.mode === "none") return css;
+    return this.cached(css, () => toCss(daltonize(toRgb(css), this.mode)));
+  }
+
+  /** `fgCss` as painted over `bgCss`: daltonized, then brought up to the minimum contrast. */
+  foreground(fgCss: string, bgCss: string): string {
+    if (!this.active) return fgCss;
+    return this.cached(`${fgCss}|${bgCss}`, () => {
+      const fg = daltonize(toRgb(fgCss), this.mode);
+      const bg = daltonize(toRgb(bgCss), this.mode);
+      return toCss(ensureContrast(fg, bg, this.minimumContrastRatio));
+    });
+  }
+
+  private cached(key: string, compute: () => string): string {
+    let value = this.cache.get(key);
+    if (value === undefined) {
+      if (this.cache.size >= CACHE_LIMIT) this.cache.clear();
+      value = compute();
+      this.cache.set(key, value);
+    }
+    return value;
+  }
+}
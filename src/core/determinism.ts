@@ -0,0 +1,57 @@
+/**
+ * Deterministic time and randomness for reproducible previews.
+ *
+ * A virtual clock only moves when the guest polls with a clock subscription,
+ * and `random_get` draws from a seeded PRNG, so the same inputs produce
+ * byte-identical output across loads.
+ */
+
+import type { WasiClockSource } from "../types.js";
+
+export interface VirtualClock extends WasiClockSource {
+  /** Move the clock forward by `ns` nanoseconds. */
+  advance(ns: bigint): void;
+}
+
+/** Create a clock frozen at `epochMs` until advanced by the bridge. */
+export function createVirtualClock(epochMs = 0): VirtualClock {
+  const epochNs = BigInt(Math.max(0, Math.floor(epochMs))) * 1_000_000n;
+  let elapsedNs = 0n;
+  return {
+    realtime: () => epochNs + elapsedNs,
+    monotonic: () => elapsedNs,
+    realtimeResolution: 1n,
+    monotonicResolution: 1n,
+    advance(ns: bigint) {
+      if (ns > 0n) elapsedNs += ns;
+    },
+  };
+}
+
+/** Byte-filling PRNG; `state` seeds an identical generator from where this one is. */
+export interface SeededRandom {
+  (target: Uint8Array): void;
+  readonly state: number;
+}
+
+/** Seeded PRNG (mulberry32) that fills byte buffers. */
+export function createSeededRandom(seed: number): SeededRandom {
+  let state = seed >>> 0;
+  const next = () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return (t ^ (t >>> 14)) >>> 0;
+  };
+  const fill = (target: Uint8Array) => {
+    for (let i = 0; i < target.length; i += 4) {
+      let word = next();
+      for (let j = i; j < Math.min(i + 4, target.length); j++) {
+        target[j] = word & 0xff;
+        word >>>= 8;
+      }
+    }
+  };
+  return Object.defineProperty(fill, "state", { get: () => state }) as SeededRandom;
+}
@@ -0,0 +1,131 @@
+/**
+ * DOM renderer — rows of styled `<span>`s instead of canvas pixels, so the
+ * screen is readable by screen readers, selectable and found by find-in-page.
+ */
+
+import type { GhosttyTheme } from "../types.js";
+import { ColorAdjuster, type TerminalColorOptions } from "./contrast.js";
+import { GLYPH_BOLD, GLYPH_ITALIC } from "./glyph-atlas.js";
+import {
+  fontFamilyFor,
+  measureCellSize,
+  pickFontOptions,
+  viewportRowToSpans,
+  type StyledSpan,
+  type TerminalFontOptions,
+  type ViewportData,
+} from "./libghostty.js";
+
+export class DomRenderer {
+  /** Focus and input target, in place of the canvas. */
+  readonly element: HTMLDivElement;
+  private cell: { w: number; h: number };
+  private rowElements: HTMLDivElement[] = [];
+  private readonly font: TerminalFontOptions;
+  private readonly colors: ColorAdjuster;
+
+  constructor(
+    container: HTMLElement,
+    private cols: number,
+    private rows: number,
+    private readonly fontSize: number,
+    private readonly fontFamily: string,
+    private readonly theme: GhosttyTheme,
+    font: TerminalFontOptions = {},
+    colors: TerminalColorOptions = {}
+  ) {
+    this.font = pickFontOptions(font);
+    this.colors = new ColorAdjuster(colors);
+    this.cell = measureCellSize(fontSize, fontFamily, this.font);
+    this.element = document.createElement("div");
+    Object.assign(this.element.style, {
+      fontFamily: fontFamilyFor(fontFamily, 0, this.font),
+      fontSize: `${fontSize}px`,
+      lineHeight: `${this.cell.h}px`,
+      letterSpacing: this.font.letterSpacing ? `${this.font.letterSpacing}px` : "",
+      fontVariantLigatures: this.font.ligatures ? "normal" : "none",
+      color: this.colors.foreground(theme.foreground, theme.background),
+      background: this.colors.background(theme.background),
+      whiteSpace: "pre",
+      overflow: "hidden",
+    });
+    container.appendChild(this.element);
+    this.resize(cols, rows);
+  }
+
+  get cellSize() {
+    return this.cell;
+  }
+
+  /** Rebuild `dirtyRows` of the viewport (every row when omitted or after a size change). */
+  render(viewport: ViewportData, dirtyRows?: readonly number[]) {
+    let rowsToPaint = dirtyRows;
+    if (viewport.cols !== this.cols || viewport.rows !== this.rows) {
+      this.resize(viewport.cols, viewport.rows);
+      rowsToPaint = undefined;
+    }
+    if (rowsToPaint) {
+      for (const y of rowsToPaint) {
+        if (y < viewport.rows) this.paintRow(viewport, y);
+      }
+    } else {
+      for (let y = 0; y < viewport.rows; y++) {
+        this.paintRow(viewport, y);
+      }
+    }
+  }
+
+  /** Re-measure after the font changed underneath, e.g. a web font finished loading. */
+  remeasureFont() {
+    this.cell = measureCellSize(this.fontSize, this.fontFamily, this.font);
+    this.element.style.lineHeight = `${this.cell.h}px`;
+    for (const row of this.rowElements) {
+      row.style.height = `${this.cell.h}px`;
+    }
+    this.resize(this.cols, this.rows);
+  }
+
+  dispose() {
+    this.element.remove();
+  }
+
+  private paintRow(viewport: ViewportData, y: number) {
+    const spans = viewportRowToSpans(viewport, y, this.theme);
+    this.rowElements[y].replaceChildren(...spans.map((span) => this.spanElement(span)));
+  }
+
+  /** Plain inline spans keep each row one run of text for find-in-page and copying. */
+  private spanElement(span: StyledSpan): HTMLSpanElement {
+    const element = document.createElement("span");
+    element.textContent = span.text;
+    const style = (span.bold ? GLYPH_BOLD : 0) | (span.italic ? GLYPH_ITALIC : 0);
+    // Spans without a per-style family inherit the container's.
+    const ownFamily = (span.italic && this.font.fontFamilyItalic) || (span.bold && this.font.fontFamilyBold);
+    Object.assign(element.style, {
+      fontFamily: ownFamily ? fontFamilyFor(this.fontFamily, style, this.font) : "",
+      color: this.colors.foreground(span.fg, span.bg ?? this.theme.background),
+      background: span.bg ? this.colors.background(span.bg) : "",
+      fontWeight: span.bold ? "bold" : "",
+      fontStyle: span.italic ? "italic" : "",
+      textDecoration: span.underline ? "underline" : "",
+      opacity: span.faint ? "0.5" : "",
+    });
+    return element;
+  }
+
+  private resize(cols: number, rows: number) {
+    this.cols = cols;
+    this.rows = rows;
+    this.element.style.width = `${cols * this.cell.w}px`;
+    this.element.style.height = `${rows * this.cell.h}px`;
+    while (this.rowElements.length > rows) {
+      this.rowElements.pop()?.remove();
+    }
+    while (this.rowElements.length < rows) {
+      const row = document.createElement("div");
+      row.style.height = `${this.cell.h}px`;
+      this.element.appendChild(row);
+      this.rowElements.push(row);
+    }
+  }
+}
@@ -0,0 +1,109 @@
+/**
+ * Screen export — serialize a viewport cell buffer to SVG or HTML. Pure
+ * string builders, usable in Node as well as the browser.
+ */
+
+import type { GhosttyTheme } from "../types.js";
+import { resolveTheme, viewportToSpans, type StyledSpan, type ViewportData } from "./libghostty.js";
+
+export interface ScreenExportOptions {
+  theme?: Partial<GhosttyTheme>;
+  /** Default: 14 */
+  fontSize?: number;
+  /** Default: "monospace" */
+  fontFamily?: string;
+  /** Cell size in px. Default: estimated from fontSize, like measureCellSize without a canvas */
+  cellWidth?: number;
+  cellHeight?: number;
+}
+
+function escapeXml(text: string): string {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/"/g, "&quot;");
+}
+
+function resolveExportOptions(options: ScreenExportOptions) {
+  const fontSize = options.fontSize ?? 14;
+  return {
+    theme: resolveTheme(options.theme),
+    fontSize,
+    fontFamily: options.fontFamily ?? "monospace",
+    cellWidth: options.cellWidth ?? Math.ceil(fontSize * 0.6),
+    cellHeight: options.cellHeight ?? Math.ceil(fontSize * 1.2),
+  };
+}
+
+function cssDeclarations(span: StyledSpan): string {
+  let css = `color:${span.fg}`;
+  if (span.bg) css += `;background:${span.bg}`;
+  if (span.bold) css += ";font-weight:bold";
+  if (span.italic) css += ";font-style:italic";
+  if (span.underline) css += ";text-decoration:underline";
+  if (span.faint) css += ";opacity:0.5";
+  return css;
+}
+
+/** Styled SVG of the screen: one `<text>` per style span, backgrounds as `<rect>`s. */
+export function viewportToSvg(viewport: ViewportData, options: ScreenExportOptions = {}): string {
+  const { theme, fontSize, fontFamily, cellWidth, cellHeight } = resolveExportOptions(options);
+  const width = viewport.cols * cellWidth;
+  const height = viewport.rows * cellHeight;
+  const baseline = Math.round(cellHeight * 0.8);
+  const backgrounds: string[] = [];
+  const texts: string[] = [];
+
+  viewportToSpans(viewport, theme).forEach((line, y) => {
+    for (const span of line) {
+      const x = span.col * cellWidth;
+      const spanWidth = span.width * cellWidth;
+      if (span.bg) {
+        backgrounds.push(
+          `<rect x="${x}" y="${y * cellHeight}" width="${spanWidth}" height="${cellHeight}" fill="${span.bg}"/>`
+        );
+      }
+      if (!span.text.trim()) continue;
+      let attrs = `x="${x}" y="${y * cellHeight + baseline}" fill="${span.fg}"`;
+      attrs += ` textLength="${spanWidth}" lengthAdjust="spacingAndGlyphs"`;
+      if (span.bold) attrs += ` font-weight="bold"`;
+      if (span.italic) attrs += ` font-style="italic"`;
+      if (span.underline) attrs += ` text-decoration="underline"`;
+      if (span.faint) attrs += ` opacity="0.5"`;
+      texts.push(`<text ${attrs}>${escapeXml(span.text)}</text>`);
+    }
+  });
+
+  return [
+    `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}" viewBox="0 0 ${width} ${height}"` +
+      ` font-family="${escapeXml(fontFamily)}" font-size="${fontSize}" xml:space="preserve">`,
+    `<rect width="100%" height="100%" fill="${theme.background}"/>`,
+    ...backgrounds,
+    ...texts,
+    "</svg>",
+  ].join("\n");
+}
+
+/** Semantic HTML of the screen: a `<pre>` with a styled `<span>` per non-default style span. */
+export function viewportToHtml(viewport: ViewportData, options: ScreenExportOptions = {}): string {
+  const { theme, fontSize, fontFamily, cellHeight } = resolveExportOptions(options);
+  const lines = viewportToSpans(viewport, theme).map((line) => {
+    let html = "";
+    let column = 0;
+    for (const span of line) {
+      if (span.col > column) html += " ".repeat(span.col - column);
+      const plain =
+        span.fg === theme.foreground && !span.bg && !span.bold && !span.italic && !span.underline && !span.faint;
+      html += plain
+        ? escapeXml(span.text)
+        : `<span style="${cssDeclarations(span)}">${escapeXml(span.text)}</span>`;
+      column = span.col + span.width;
+    }
+    return html;
+  });
+  const preStyle =
+    `margin:0;padding:0;background:${theme.background};color:${theme.foreground};` +
+    `font-family:${fontFamily};font-size:${fontSize}px;line-height:${cellHeight}px`;
+  return `<pre class="tui-screen" style="${escapeXml(preStyle)}">${lines.join("\n")}</pre>`;
+}
@@ -0,0 +1,134 @@
+/**
+ * Glyph atlas — rasterize each (codepoint, bold, italic, color) once into an
+ * offscreen canvas, then blit it with `drawImage` on every later frame.
+ */
+
+/** Atlas edge length in device pixels. When full, the atlas is cleared and refilled. */
+const ATLAS_SIZE = 1024;
+
+export const GLYPH_BOLD = 1;
+export const GLYPH_ITALIC = 2;
+
+type AtlasCanvas = OffscreenCanvas | HTMLCanvasElement;
+type AtlasContext = OffscreenCanvasRenderingContext2D | CanvasRenderingContext2D;
+
+interface GlyphSlot {
+  x: number;
+  y: number;
+  w: number;
+}
+
+export interface GlyphCellMetrics {
+  width: number;
+  height: number;
+  baseline: number;
+}
+
+export class GlyphAtlas {
+  private readonly slots = new Map<string, GlyphSlot | null>();
+  private readonly slotHeight: number;
+  private shelfX = 0;
+  private shelfY = 0;
+
+  private constructor(
+    private readonly canvas: AtlasCanvas,
+    private readonly ctx: AtlasContext,
+    private readonly cell: GlyphCellMetrics,
+    private readonly dpr: number,
+    private readonly fontFor: (style: number) => string
+  ) {
+    this.slotHeight = Math.ceil(cell.height * dpr);
+  }
+
+  /** Null when no 2D canvas is available (e.g. a context-less test DOM). */
+  static create(
+    cell: GlyphCellMetrics,
+    dpr: number,
+    fontFor: (style: number) => string
+  ): GlyphAtlas | null {
+    if (typeof OffscreenCanvas !== "undefined") {
+      const canvas = new OffscreenCanvas(ATLAS_SIZE, ATLAS_SIZE);
+      const ctx = canvas.getContext("2d");
+      return ctx ? new GlyphAtlas(canvas, ctx, cell, dpr, fontFor) : null;
+    }
+    if (typeof document === "undefined") return null;
+    const canvas = document.createElement("canvas");
+    canvas.width = ATLAS_SIZE;
+    canvas.height = ATLAS_SIZE;
+    const ctx = canvas.getContext("2d");
+    return ctx ? new GlyphAtlas(canvas, ctx, cell, dpr, fontFor) : null;
+  }
+
+  /**
+   * Blit a glyph `columns` cells wide at (`dx`, `dy`) in `target`'s CSS
+   * pixels. Returns false when the glyph can't be cached (it is larger than
+   * the atlas), so the caller should draw it with `fillText` instead.
+   */
+  draw(
+    target: AtlasContext,
+    codepoint: number,
+    style: number,
+    color: string,
+    columns: number,
+    dx: number,
+    dy: number
+  ): boolean {
+    const key = `${codepoint}:${style}:${columns}:${color}`;
+    let slot = this.slots.get(key);
+    if (slot === undefined) {
+      slot = this.rasterize(codepoint, style, color, columns);
+      this.slots.set(key, slot);
+    }
+    if (!slot) return false;
+    target.drawImage(
+      this.canvas,
+      slot.x,
+      slot.y,
+      slot.w,
+      this.slotHeight,
+      dx,
+      dy,
+      slot.w / this.dpr,
+      this.slotHeight / this.dpr
+    );
+    return true;
+  }
+
+  private rasterize(codepoint: number, style: number, color: string, columns: number): GlyphSlot | null {
+    const w = Math.ceil(this.cell.width * columns * this.dpr);
+    const h = this.slotHeight;
+    if (w > ATLAS_SIZE || h > ATLAS_SIZE || codepoint > 0x10ffff) return null;
+
+    if (this.shelfX + w > ATLAS_SIZE) {
+      this.shelfX = 0;
+      this.shelfY += h;
+    }
+    if (this.shelfY + h > ATLAS_SIZE) {
+      this.reset();
+    }
+    const slot = { x: this.shelfX, y: this.shelfY, w };
+    this.shelfX += w;
+
+    const ctx = this.ctx;
+    ctx.save();
+    ctx.beginPath();
+    ctx.rect(slot.x, slot.y, w, h);
+    ctx.clip();
+    ctx.setTransform(this.dpr, 0, 0, this.dpr, slot.x, slot.y);
+    ctx.font = this.fontFor(style);
+    ctx.textBaseline = "alphabetic";
+    ctx.textAlign = "left";
+    ctx.fillStyle = color;
+    ctx.fillText(String.fromCodePoint(codepoint), 0, this.cell.baseline);
+    ctx.restore();
+    return slot;
+  }
+
+  /** Drop every cached glyph; blits already on screen are unaffected. */
+  private reset() {
+    this.slots.clear();
+    this.shelfX = 0;
+    this.shelfY = 0;
+    this.ctx.clearRect(0, 0, ATLAS_SIZE, ATLAS_SIZE);
+  }
+}
@@ -0,0 +1,185 @@
+/**
+ * Headless terminal — libghostty without a DOM or canvas, for running
+ * guests under Node (tests, build scripts).
+ */
+
+import type { GhosttyTheme } from "../types.js";
+import {
+  EXPECTED_CELL_SIZE,
+  FLAG_BG_EXPLICIT,
+  FLAG_BOLD,
+  FLAG_FAINT,
+  FLAG_FG_EXPLICIT,
+  FLAG_INVERSE,
+  FLAG_INVISIBLE,
+  FLAG_ITALIC,
+  FLAG_UNDERLINE,
+  cellColumns,
+  loadLibGhostty,
+  normalizeEol,
+  resolveTheme,
+  viewportToText,
+  type ViewportData,
+  type TerminalState,
+} from "./libghostty.js";
+import { serializeScreen } from "./serialize.js";
+
+export interface TerminalCell {
+  /** Grapheme for the cell; "" for the trailing half of a wide character */
+  char: string;
+  /** Explicit foreground as `#rrggbb`, or null for the theme default */
+  fg: string | null;
+  /** Explicit background as `#rrggbb`, or null for the theme default */
+  bg: string | null;
+  bold: boolean;
+  italic: boolean;
+  underline: boolean;
+  inverse: boolean;
+  invisible: boolean;
+  faint: boolean;
+  /** Columns occupied: 1, 2 for wide characters, 0 for their spacer */
+  width: number;
+}
+
+export interface HeadlessTerminalOptions {
+  cols?: number;
+  rows?: number;
+  theme?: Partial<GhosttyTheme>;
+  /** Translate "\n" to "\r\n" like the live surface. Default: true */
+  convertEol?: boolean;
+  /** libghostty wasm: URL, file path (Node) or the bytes. Default: the bundled ghostty-vt.wasm */
+  wasm?: string | URL | BufferSource;
+}
+
+export interface HeadlessTerminal {
+  readonly cols: number;
+  readonly rows: number;
+  write(data: string | Uint8Array): void;
+  resize(cols: number, rows: number): void;
+  /** Plain text of the visible screen, one line per row with trailing spaces trimmed. */
+  getText(): string;
+  /** Decoded cells of the visible screen, row by row. */
+  getCells(): TerminalCell[][];
+  /** Raw viewport cell buffer, for serializers that work on it directly. */
+  getViewportData(): ViewportData;
+  /** Cursor, margins and modes, or null if the wasm predates `ghostty_terminal_get_state`. */
+  getState(): TerminalState | null;
+  /** ANSI that reproduces the screen, cursor and modes when written into a fresh terminal. */
+  serialize(): string;
+  /** Responses (DA, DSR, ...) the terminal wants to send back to the app. */
+  drainResponses(): string[];
+  dispose(): void;
+}
+
+function hex(view: DataView, offset: number): string {
+  let out = "#";
+  for (let i = 0; i < 3; i++) {
+    out += view.getUint8(offset + i).toString(16).padStart(2, "0");
+  }
+  return out;
+}
+
+/** Decode a viewport cell buffer into per-cell records. */
+export function viewportToCells(viewport: ViewportData): TerminalCell[][] {
+  const { cols, rows, buffer } = viewport;
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  const lines: TerminalCell[][] = [];
+  for (let y = 0; y < rows; y++) {
+    const line: TerminalCell[] = [];
+    for (let x = 0; x < cols; x++) {
+      const base = (y * cols + x) * EXPECTED_CELL_SIZE;
+      if (base + EXPECTED_CELL_SIZE > buffer.byteLength) break;
+      const codepoint = view.getUint32(base, true);
+      const flags = view.getUint8(base + 10);
+      const width = cellColumns(view, base, x);
+      let char = "";
+      if (width > 0) {
+        try {
+          char = codepoint === 0 ? " " : String.fromCodePoint(codepoint);
+        } catch {
+          char = " ";
+        }
+      }
+      line.push({
+        char,
+        fg: flags & FLAG_FG_EXPLICIT ? hex(view, base + 4) : null,
+        bg: flags & FLAG_BG_EXPLICIT ? hex(view, base + 7) : null,
+        bold: (flags & FLAG_BOLD) !== 0,
+        italic: (flags & FLAG_ITALIC) !== 0,
+        underline: (flags & FLAG_UNDERLINE) !== 0,
+        inverse: (flags & FLAG_INVERSE) !== 0,
+        invisible: (flags & FLAG_INVISIBLE) !== 0,
+        faint: (flags & FLAG_FAINT) !== 0,
+        width,
+      });
+    }
+    lines.push(line);
+  }
+  return lines;
+}
+
+/** Create a libghostty terminal with no DOM dependencies. */
+export async function createHeadlessTerminal(
+  options: HeadlessTerminalOptions = {}
+): Promise<HeadlessTerminal> {
+  const runtime = await loadLibGhostty(options.wasm);
+  const convertEol = options.convertEol ?? true;
+  const terminal = runtime.createTerminal(
+    Math.max(1, Math.floor(options.cols ?? 80)),
+    Math.max(1, Math.floor(options.rows ?? 24)),
+    resolveTheme(options.theme)
+  );
+  const decoder = new TextDecoder();
+  let disposed = false;
+
+  const assertLive = () => {
+    if (disposed) throw new Error("Headless terminal has been disposed.");
+  };
+
+  return {
+    get cols() {
+      return terminal.cols;
+    },
+    get rows() {
+      return terminal.rows;
+    },
+    write(data) {
+      assertLive();
+      const text = typeof data === "string" ? data : decoder.decode(data, { stream: true });
+      terminal.write(convertEol ? normalizeEol(text) : text);
+    },
+    resize(cols, rows) {
+      assertLive();
+      terminal.resize(Math.max(1, Math.floor(cols)), Math.max(1, Math.floor(rows)));
+    },
+    getText() {
+      assertLive();
+      return viewportToText(terminal.getViewportData(false));
+    },
+    getCells() {
+      assertLive();
+      return viewportToCells(terminal.getViewportData(false));
+    },
+    getViewportData() {
+      assertLive();
+      return terminal.getViewportData(false);
+    },
+    getState() {
+      assertLive();
+      return terminal.getState();
+    },
+    serialize() {
+      assertLive();
+      return serializeScreen(terminal.getViewportData(false), terminal.getState());
+    },
+    drainResponses() {
+      assertLive();
+      return terminal.drainResponses();
+    },
+    dispose() {
+      if (disposed) return;
+      disposed = true;
+      terminal.dispose();
+    },
+  };
+}
@@ -1,3 +1,66 @@
-export { createMiniTerminalSurface, loadLibGhostty, measureCellSize } from "./libghostty.js";
-export { resolveTuiPreviewProps } from "./normalize.js";
-export { WasiBridge, WasiExitError, instantiateApp } from "./wasi.js";
+export {
+  CastPlayer,
+  CastRecorder,
+  loadAsciicast,
+  parseAsciicast,
+  parseResizeEvent,
+  serializeAsciicast,
+} from "./asciicast.js";
+export { drawBoxGlyph, isBoxGlyph } from "./box-drawing.js";
+export { contrastRatio, daltonize, ensureContrast, relativeLuminance } from "./contrast.js";
+export type { TerminalColorOptions } from "./contrast.js";
+export { createSeededRandom, createVirtualClock } from "./determinism.js";
+export { viewportToHtml, viewportToSvg } from "./export.js";
+export type { ScreenExportOptions } from "./export.js";
+export { createHeadlessTerminal, viewportToCells } from "./headless.js";
+export type { HeadlessTerminal, HeadlessTerminalOptions, TerminalCell } from "./headless.js";
+export {
+  DEFAULT_FONT_LOAD_TIMEOUT,
+  createMiniTerminalSurface,
+  encodeKey,
+  encodeKeys,
+  loadLibGhostty,
+  loadTerminalFont,
+  measureCellSize,
+  resolveTheme,
+  viewportRowText,
+  viewportRowToSpans,
+  viewportToSpans,
+  viewportToText,
+  whenFontLoaded,
+} from "./libghostty.js";
+export type { RgbColor, TerminalFontOptions, TerminalState, ViewportData } from "./libghostty.js";
+export { reduceTermSize, resolveTuiPreviewProps } from "./normalize.js";
+export type { TermSizeAction, TermSizeState } from "./normalize.js";
+export {
+  ansiToSpans,
+  capturePoster,
+  decodeCellBuffer,
+  encodeCellBuffer,
+  posterToSpans,
+} from "./poster.js";
+export { ScriptPlayer, normalizeScriptStep, parseScriptStep } from "./script.js";
+export { serializeScreen } from "./serialize.js";
+export { captureSession, decodeSession, encodeSession, restoreSession } from "./session.js";
+export type { SessionTerminal } from "./session.js";
+export { describeStyles, runTui } from "./testing.js";
+export type { RunTuiOptions, TuiSnapshot } from "./testing.js";
+export {
+  THEME_PRESETS,
+  parseBase16Yaml,
+  parseGhosttyTheme,
+  parseItermColors,
+  parseWindowsTerminalScheme,
+} from "./themes.js";
+export type { ThemePresetName } from "./themes.js";
+export {
+  RESUME_EXPORT,
+  WasiBridge,
+  WasiExitError,
+  createDefaultClock,
+  feedStdin,
+  hasJSPI,
+  instantiateApp,
+} from "./wasi.js";
+export type { InstantiateAppOptions, WasiApp } from "./wasi.js";
+export { createWorkerTerminalSurface, supportsWorkerRendering } from "./worker-surface.js";
@@ -1,19 +1,38 @@
-import type { GhosttyTheme } from "../types.js";
-
-const EXPECTED_CELL_SIZE = 16;
+import type { GhosttyTheme, TuiAnnounceVerbosity, TuiRenderer } from "../types.js";
+import { drawBoxGlyph, isBoxGlyph } from "./box-drawing.js";
+import { ColorAdjuster, pickColorOptions, type TerminalColorOptions } from "./contrast.js";
+import { DomRenderer } from "./dom-renderer.js";
+import { GLYPH_BOLD, GLYPH_ITALIC, GlyphAtlas } from "./glyph-atlas.js";
+import { LiveRegion } from "./live-region.js";
+import { loadBytes } from "./load.js";
+
+export const EXPECTED_CELL_SIZE = 16;
 const EXPECTED_TERMINAL_CONFIG_SIZE = 80;
+const EXPECTED_STATE_SIZE = 44;
 const DEFAULT_WASM_URL = new URL("./ghostty-vt.wasm", import.meta.url).href;
 
-const FLAG_BOLD = 1 << 0;
-const FLAG_ITALIC = 1 << 1;
-const FLAG_UNDERLINE = 1 << 2;
-const FLAG_FG_EXPLICIT = 1 << 3;
-const FLAG_INVERSE = 1 << 4;
-const FLAG_INVISIBLE = 1 << 5;
-const FLAG_BG_EXPLICIT = 1 << 6;
-const FLAG_FAINT = 1 << 7;
-
-const ANSI_THEME_KEYS: (keyof GhosttyTheme)[] = [
+export const FLAG_BOLD = 1 << 0;
+export const FLAG_ITALIC = 1 << 1;
+export const FLAG_UNDERLINE = 1 << 2;
+export const FLAG_FG_EXPLICIT = 1 << 3;
+export const FLAG_INVERSE = 1 << 4;
+export const FLAG_INVISIBLE = 1 << 5;
+export const FLAG_BG_EXPLICIT = 1 << 6;
+export const FLAG_FAINT = 1 << 7;
+
+const STATE_WRAPAROUND = 1 << 0;
+const STATE_PENDING_WRAP = 1 << 1;
+const STATE_ORIGIN = 1 << 2;
+const STATE_LEFT_RIGHT_MARGIN = 1 << 3;
+const STATE_CURSOR_VISIBLE = 1 << 4;
+const STATE_ALT_SCREEN = 1 << 5;
+const STATE_CURSOR_KEYS = 1 << 6;
+const STATE_BRACKETED_PASTE = 1 << 7;
+const STYLE_HAS_FG = 1 << 0;
+const STYLE_HAS_BG = 1 << 1;
+
+/** Theme keys for palette indices 0–15, in order. */
+export const ANSI_THEME_KEYS: (keyof GhosttyTheme)[] = [
   "black",
   "red",
   "green",
@@ -71,8 +90,14 @@ interface LibGhosttyExports extends WebAssembly.Exports {
   ghostty_render_state_is_row_dirty(handle: number, row: number): boolean;
   ghostty_render_state_mark_clean(handle: number): void;
   ghostty_render_state_get_viewport(handle: number, bufPtr: number, bufLen: number): number;
+  /** Missing from older builds; see LibGhosttyTerminal.takeDirtyRows. */
+  ghostty_render_state_get_row?(handle: number, row: number, bufPtr: number, bufLen: number): number;
   ghostty_terminal_has_response(handle: number): boolean;
   ghostty_terminal_read_response(handle: number, bufPtr: number, bufLen: number): number;
+  /** Missing from older builds; see LibGhosttyTerminal.getState. */
+  ghostty_terminal_get_state?(handle: number, bufPtr: number, bufLen: number): number;
+  /** Missing from older builds; see LibGhosttyTerminal.takeTitle. */
+  ghostty_terminal_take_title?(handle: number, bufPtr: number, bufLen: number): number;
 }
 
 interface GhosttyTerminalConfig {
@@ -82,13 +107,98 @@ interface GhosttyTerminalConfig {
   palette: number[];
 }
 
+export interface ViewportData {
+  cols: number;
+  rows: number;
+  /** `cols * rows` encoded cells, 16 bytes each. */
+  buffer: Uint8Array;
+}
+
+export interface RgbColor {
+  r: number;
+  g: number;
+  b: number;
+}
+
+/** Cursor, margins, modes and pen style. Positions and margins are 0-based and absolute. */
+export interface TerminalState {
+  cursorX: number;
+  cursorY: number;
+  savedX: number;
+  savedY: number;
+  scrollTop: number;
+  scrollBottom: number;
+  scrollLeft: number;
+  scrollRight: number;
+  wraparound: boolean;
+  pendingWrap: boolean;
+  originMode: boolean;
+  leftRightMargin: boolean;
+  /** DECTCEM (?25) */
+  cursorVisible: boolean;
+  /** Any of ?47, ?1047 or ?1049 */
+  altScreen: boolean;
+  /** DECCKM (?1) */
+  applicationCursorKeys: boolean;
+  /** ?2004 */
+  bracketedPaste: boolean;
+  /** Current SGR attributes, as FLAG_* bits (the color-explicit bits are unused) */
+  styleFlags: number;
+  fg: RgbColor | null;
+  bg: RgbColor | null;
+}
+
 interface FontMetrics {
   width: number;
   height: number;
   baseline: number;
+  /** Unrounded advance of "M"; `width` is this plus letter spacing, rounded up to whole pixels. */
+  advance: number;
+}
+
+/** Consecutive same-style cells drawn with a single `fillText`. */
+interface TextRun {
+  text: string;
+  x: number;
+  /** Column just past the run. */
+  end: number;
+  font: string;
+  color: string;
+  faint: boolean;
+}
+
+/** A page canvas, or one transferred to a worker with `transferControlToOffscreen()`. */
+export type RenderCanvas = HTMLCanvasElement | OffscreenCanvas;
+type RenderContext = CanvasRenderingContext2D | OffscreenCanvasRenderingContext2D;
+
+/** Font faces and spacing beyond the primary size and family. */
+export interface TerminalFontOptions {
+  /** Family for bold cells. Default: `fontFamily` */
+  fontFamilyBold?: string;
+  /** Family for italic cells. Default: `fontFamily` */
+  fontFamilyItalic?: string;
+  /** Families tried after the primary one for missing glyphs, e.g. a Nerd Font symbol font and an emoji font. */
+  fallbackFonts?: readonly string[];
+  /** Cell height as a multiple of the measured font height. Default: 1 */
+  lineHeight?: number;
+  /** Extra horizontal pixels per cell; may be negative. Default: 0 */
+  letterSpacing?: number;
+  /**
+   * Draw ASCII runs as whole strings so the font's programming ligatures can
+   * form. Needs a font whose advance is a whole pixel and no letter spacing,
+   * since spaced text doesn't ligate. Default: false
+   */
+  ligatures?: boolean;
 }
 
-export interface MiniTerminalSurfaceOptions {
+export interface MiniRendererOptions extends TerminalFontOptions, TerminalColorOptions {
+  /** Blit glyphs from an offscreen atlas. Default: true; false draws batched `fillText` runs. */
+  glyphCache?: boolean;
+  /** Backing store scale. Default: `window.devicePixelRatio`; workers must pass it. */
+  devicePixelRatio?: number;
+}
+
+export interface MiniTerminalSurfaceOptions extends TerminalFontOptions, TerminalColorOptions {
   container: HTMLElement;
   /** Explicit cell dimensions — used directly when provided. */
   cols?: number;
@@ -103,6 +213,18 @@ export interface MiniTerminalSurfaceOptions {
   interactive: boolean;
   showCursor: boolean;
   onInput?: (data: string) => void;
+  /** Terminal replies (DA, DSR, ...) to queries in written output; without it they queue for `drainResponses`. */
+  onResponse?: (data: string) => void;
+  /** Default: "canvas" */
+  renderer?: TuiRenderer;
+  /** Live region verbosity. Default: "rows" */
+  announce?: TuiAnnounceVerbosity;
+  /** Called when the app sets the window title (OSC 0/2). */
+  onTitle?: (title: string) => void;
+  /** Milliseconds to wait for a web font before measuring with a fallback. Default: 3000 */
+  fontLoadTimeout?: number;
+  /** Called when `cellSize` changes after creation (a late web font, a new devicePixelRatio). */
+  onCellSizeChange?: (cellSize: { w: number; h: number }) => void;
   wasmUrl?: string | URL;
 }
 
@@ -111,11 +233,24 @@ export interface MiniTerminalSurface {
   rows: number;
   cellSize: { w: number; h: number };
   write(text: string): void;
+  /** Resize the terminal grid and canvas without recreating the surface. */
+  resize(cols: number, rows: number): void;
+  /** Skip canvas repaints while suspended; output still reaches the terminal. */
+  setSuspended(suspended: boolean): void;
   drainResponses(): string[];
+  /** Plain text of the visible screen, one line per row with trailing spaces trimmed. */
+  getText(): string;
+  /** Copy of the visible cell buffer, for the SVG/HTML serializers. */
+  getViewportData(): ViewportData;
+  /** Cursor, margins and modes, or null if the wasm predates ghostty_terminal_get_state. */
+  getState(): TerminalState | null;
+  /** PNG of the canvas at device pixel ratio. */
+  toPng(): Promise<Blob>;
+  focus(): void;
   dispose(): void;
 }
 
-class LibGhosttyRuntime {
+export class LibGhosttyRuntime {
   constructor(
     private readonly wasm: LibGhosttyExports,
     private readonly abi: { cellSize: number; terminalConfigSize: number }
@@ -155,9 +290,11 @@ class LibGhosttyRuntime {
   }
 }
 
-class LibGhosttyTerminal {
+export class LibGhosttyTerminal {
   private viewportPtr = 0;
   private viewportLen = 0;
+  /** Cell grid as of the last takeDirtyRows(); invalid once anything else marks rows clean. */
+  private mirror: ViewportData | null = null;
 
   constructor(
     private readonly wasm: LibGhosttyExports,
@@ -198,6 +335,17 @@ class LibGhosttyTerminal {
     return this.wasm.ghostty_render_state_update(this.handle) !== 0;
   }
 
+  /** Read every pending response (DA, DSR, ...) the app expects on stdin. */
+  drainResponses(): string[] {
+    const responses: string[] = [];
+    while (this.hasResponse()) {
+      const response = this.readResponse();
+      if (!response) break;
+      responses.push(response);
+    }
+    return responses;
+  }
+
   readResponse(maxBytes = 4096): string | null {
     const ptr = this.wasm.ghostty_wasm_alloc_u8_array(maxBytes);
     if (!ptr) {
@@ -213,31 +361,23 @@ class LibGhosttyTerminal {
     }
   }
 
-  getViewportData(): {
-    cols: number;
-    rows: number;
-    buffer: Uint8Array;
-  } {
+  /** Copy the viewport cell buffer. Pass `markClean = false` to leave dirty state for the renderer. */
+  getViewportData(markClean = true): ViewportData {
     const cols = this.wasm.ghostty_render_state_get_cols(this.handle);
     const rows = this.wasm.ghostty_render_state_get_rows(this.handle);
     this.cols = cols;
     this.rows = rows;
-    const required = Math.max(1, cols * rows * this.cellSize);
-    if (required > this.viewportLen || this.viewportPtr === 0) {
-      this.releaseViewport();
-      this.viewportPtr = this.wasm.ghostty_wasm_alloc_u8_array(required);
-      this.viewportLen = required;
-      if (!this.viewportPtr) {
-        throw new Error("Failed to allocate libghostty viewport buffer.");
-      }
-    }
+    this.ensureViewportBuffer(cols * rows * this.cellSize);
     const written = this.wasm.ghostty_render_state_get_viewport(this.handle, this.viewportPtr, this.viewportLen);
     const snapshot = new Uint8Array(written);
     if (written > 0) {
       const source = new Uint8Array(this.wasm.memory.buffer, this.viewportPtr, written);
       snapshot.set(source);
     }
-    this.wasm.ghostty_render_state_mark_clean(this.handle);
+    if (markClean) {
+      this.wasm.ghostty_render_state_mark_clean(this.handle);
+      this.mirror = null;
+    }
     return {
       cols,
       rows,
@@ -245,11 +385,110 @@ class LibGhosttyTerminal {
     };
   }
 
+  /**
+   * Bring a JS-side copy of the grid up to date by fetching only dirty rows,
+   * then mark the terminal clean. `rows` lists the rows that changed: all of
+   * them after a resize, or with a wasm that lacks `ghostty_render_state_get_row`.
+   * The returned viewport is reused across calls; copy it to keep it.
+   */
+  takeDirtyRows(): { viewport: ViewportData; rows: number[] } {
+    const cols = this.wasm.ghostty_render_state_get_cols(this.handle);
+    const rows = this.wasm.ghostty_render_state_get_rows(this.handle);
+    const getRow = this.wasm.ghostty_render_state_get_row;
+    const mirror = this.mirror;
+    if (!getRow || !mirror || mirror.cols !== cols || mirror.rows !== rows) {
+      const viewport = this.getViewportData(true);
+      this.mirror = viewport;
+      return { viewport, rows: Array.from({ length: viewport.rows }, (_, y) => y) };
+    }
+
+    const rowBytes = cols * this.cellSize;
+    this.ensureViewportBuffer(rowBytes);
+    const dirty: number[] = [];
+    for (let y = 0; y < rows; y++) {
+      if (!this.wasm.ghostty_render_state_is_row_dirty(this.handle, y)) continue;
+      const written = getRow(this.handle, y, this.viewportPtr, rowBytes);
+      mirror.buffer.set(new Uint8Array(this.wasm.memory.buffer, this.viewportPtr, written), y * rowBytes);
+      dirty.push(y);
+    }
+    this.wasm.ghostty_render_state_mark_clean(this.handle);
+    return { viewport: mirror, rows: dirty };
+  }
+
+  /** Window title (OSC 0/2) if it changed since the last call; null otherwise or on older wasm builds. */
+  takeTitle(maxBytes = 1024): string | null {
+    const takeTitle = this.wasm.ghostty_terminal_take_title;
+    if (!takeTitle) return null;
+    const ptr = this.wasm.ghostty_wasm_alloc_u8_array(maxBytes);
+    if (!ptr) {
+      throw new Error("Failed to allocate libghostty title buffer.");
+    }
+    try {
+      const written = takeTitle(this.handle, ptr, maxBytes);
+      if (written < 0) return null;
+      return new TextDecoder().decode(new Uint8Array(this.wasm.memory.buffer, ptr, written));
+    } finally {
+      this.wasm.ghostty_wasm_free_u8_array(ptr, maxBytes);
+    }
+  }
+
+  /** Cursor, margins and modes, or null if the wasm predates `ghostty_terminal_get_state`. */
+  getState(): TerminalState | null {
+    const getState = this.wasm.ghostty_terminal_get_state;
+    if (!getState) return null;
+    const ptr = this.wasm.ghostty_wasm_alloc_u8_array(EXPECTED_STATE_SIZE);
+    if (!ptr) {
+      throw new Error("Failed to allocate libghostty state buffer.");
+    }
+    try {
+      const written = getState(this.handle, ptr, EXPECTED_STATE_SIZE);
+      if (written !== EXPECTED_STATE_SIZE) return null;
+      const view = new DataView(this.wasm.memory.buffer, ptr, EXPECTED_STATE_SIZE);
+      const u32 = (index: number) => view.getUint32(index * 4, true);
+      const flags = u32(8);
+      const colors = view.getUint8(43);
+      return {
+        cursorX: u32(0),
+        cursorY: u32(1),
+        savedX: u32(2),
+        savedY: u32(3),
+        scrollTop: u32(4),
+        scrollBottom: u32(5),
+        scrollLeft: u32(6),
+        scrollRight: u32(7),
+        wraparound: hasFlag(flags, STATE_WRAPAROUND),
+        pendingWrap: hasFlag(flags, STATE_PENDING_WRAP),
+        originMode: hasFlag(flags, STATE_ORIGIN),
+        leftRightMargin: hasFlag(flags, STATE_LEFT_RIGHT_MARGIN),
+        cursorVisible: hasFlag(flags, STATE_CURSOR_VISIBLE),
+        altScreen: hasFlag(flags, STATE_ALT_SCREEN),
+        applicationCursorKeys: hasFlag(flags, STATE_CURSOR_KEYS),
+        bracketedPaste: hasFlag(flags, STATE_BRACKETED_PASTE),
+        styleFlags: view.getUint8(36),
+        fg: hasFlag(colors, STYLE_HAS_FG) ? readRgb(view, 37) : null,
+        bg: hasFlag(colors, STYLE_HAS_BG) ? readRgb(view, 40) : null,
+      };
+    } finally {
+      this.wasm.ghostty_wasm_free_u8_array(ptr, EXPECTED_STATE_SIZE);
+    }
+  }
+
   dispose() {
     this.releaseViewport();
     this.wasm.ghostty_terminal_free(this.handle);
   }
 
+  private ensureViewportBuffer(bytes: number) {
+    const required = Math.max(1, bytes);
+    if (required <= this.viewportLen && this.viewportPtr !== 0) return;
+    this.releaseViewport();
+    this.viewportPtr = this.wasm.ghostty_wasm_alloc_u8_array(required);
+    this.viewportLen = required;
+    if (!this.viewportPtr) {
+      throw new Error("Failed to allocate libghostty viewport buffer.");
+    }
+  }
+
   private releaseViewport() {
     if (this.viewportPtr === 0) return;
     this.wasm.ghostty_wasm_free_u8_array(this.viewportPtr, this.viewportLen);
@@ -258,26 +497,47 @@ class LibGhosttyTerminal {
   }
 }
 
-class MiniRenderer {
-  private readonly ctx: CanvasRenderingContext2D;
-  private readonly dpr: number;
+export class MiniRenderer {
+  private readonly ctx: RenderContext;
+  private dpr: number;
   private metrics: FontMetrics;
+  private readonly glyphCache: boolean;
+  private readonly font: TerminalFontOptions;
+  private readonly colors: ColorAdjuster;
+  private atlas: GlyphAtlas | null;
+  /**
+   * Whether a run of N cells of each style can be one fillText: the style's
+   * advance must match the regular face's and land on the cell grid,
+   * directly or via canvas `letterSpacing`.
+   */
+  private batchRuns: boolean[];
+  /** Set when the backing store was cleared; the next render paints every row. */
+  private repaintAll = true;
 
   constructor(
-    private readonly canvas: HTMLCanvasElement,
+    private readonly canvas: RenderCanvas,
     private cols: number,
     private rows: number,
     private readonly fontSize: number,
     private readonly fontFamily: string,
-    private readonly theme: GhosttyTheme
+    private readonly theme: GhosttyTheme,
+    options: MiniRendererOptions = {}
   ) {
-    const ctx = canvas.getContext("2d");
+    const ctx =
+      typeof OffscreenCanvas !== "undefined" && canvas instanceof OffscreenCanvas
+        ? canvas.getContext("2d")
+        : (canvas as HTMLCanvasElement).getContext("2d");
     if (!ctx) {
       throw new Error("Failed to create 2D canvas context.");
     }
     this.ctx = ctx;
-    this.dpr = window.devicePixelRatio || 1;
+    this.dpr = options.devicePixelRatio ?? (window.devicePixelRatio || 1);
+    this.glyphCache = options.glyphCache !== false;
+    this.font = pickFontOptions(options);
+    this.colors = new ColorAdjuster(options);
     this.metrics = this.measureFont();
+    this.batchRuns = this.canBatchRuns();
+    this.atlas = this.createAtlas();
     this.resizeCanvas(cols, rows);
   }
 
@@ -285,84 +545,164 @@ class MiniRenderer {
     return { w: this.metrics.width, h: this.metrics.height };
   }
 
-  render(viewport: { cols: number; rows: number; buffer: Uint8Array }) {
+  /**
+   * Rescale for a new devicePixelRatio (monitor change, browser zoom):
+   * re-measure the font, rebuild the glyph atlas and resize the backing
+   * store. The next render repaints every row; see remeasureFont.
+   */
+  setDevicePixelRatio(dpr: number) {
+    if (dpr === this.dpr) return;
+    this.dpr = dpr;
+    this.remeasureFont();
+  }
+
+  /** Re-measure after the font changed underneath, e.g. a web font finished loading. */
+  remeasureFont() {
+    this.metrics = this.measureFont();
+    this.batchRuns = this.canBatchRuns();
+    this.atlas = this.createAtlas();
+    this.resizeCanvas(this.cols, this.rows);
+  }
+
+  /**
+   * Paint `dirtyRows` of the viewport (every row when omitted or after a
+   * size change). Each row is cleared and clipped on its own, so rows that
+   * didn't change keep their pixels.
+   */
+  render(viewport: ViewportData, dirtyRows?: readonly number[]) {
     if (viewport.cols !== this.cols || viewport.rows !== this.rows) {
       this.cols = viewport.cols;
       this.rows = viewport.rows;
       this.resizeCanvas(viewport.cols, viewport.rows);
     }
+    const rowsToPaint = this.repaintAll ? undefined : dirtyRows;
+    this.repaintAll = false;
 
-    const { cols, rows, buffer } = viewport;
-    const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+    const view = new DataView(viewport.buffer.buffer, viewport.buffer.byteOffset, viewport.buffer.byteLength);
+    if (rowsToPaint) {
+      for (const y of rowsToPaint) {
+        if (y < viewport.rows) this.paintRow(view, viewport.cols, y);
+      }
+    } else {
+      for (let y = 0; y < viewport.rows; y++) {
+        this.paintRow(view, viewport.cols, y);
+      }
+    }
+  }
+
+  private paintRow(view: DataView, cols: number, y: number) {
     const ctx = this.ctx;
     const charW = this.metrics.width;
     const charH = this.metrics.height;
-
-    ctx.fillStyle = this.theme.background;
-    ctx.fillRect(0, 0, cols * charW, rows * charH);
-
-    for (let y = 0; y < rows; y++) {
-      for (let x = 0; x < cols; x++) {
-        const base = (y * cols + x) * EXPECTED_CELL_SIZE;
-        const width = view.getUint8(base + 11);
-        if (width === 0) continue;
-        const flags = view.getUint8(base + 10);
-        const fg = readRgb(view, base + 4);
-        const bg = readRgb(view, base + 7);
-        const inverse = hasFlag(flags, FLAG_INVERSE);
-        const fgColor = hasFlag(flags, FLAG_FG_EXPLICIT) ? rgbToCss(fg) : this.theme.foreground;
-        const bgColor = hasFlag(flags, FLAG_BG_EXPLICIT) ? rgbToCss(bg) : this.theme.background;
-        if (inverse || hasFlag(flags, FLAG_BG_EXPLICIT)) {
-          ctx.fillStyle = inverse ? fgColor : bgColor;
-          ctx.fillRect(x * charW, y * charH, width * charW, charH);
-        }
+    const rowBase = y * cols * EXPECTED_CELL_SIZE;
+    if (rowBase + cols * EXPECTED_CELL_SIZE > view.byteLength) return;
+
+    ctx.save();
+    ctx.beginPath();
+    ctx.rect(0, y * charH, cols * charW, charH);
+    ctx.clip();
+    ctx.fillStyle = this.colors.background(this.theme.background);
+    ctx.fillRect(0, y * charH, cols * charW, charH);
+
+    for (let x = 0; x < cols; x++) {
+      const base = rowBase + x * EXPECTED_CELL_SIZE;
+      const width = view.getUint8(base + 11);
+      if (width === 0) continue;
+      const flags = view.getUint8(base + 10);
+      const fg = readRgb(view, base + 4);
+      const bg = readRgb(view, base + 7);
+      const inverse = hasFlag(flags, FLAG_INVERSE);
+      const fgColor = hasFlag(flags, FLAG_FG_EXPLICIT) ? rgbToCss(fg) : this.theme.foreground;
+      const bgColor = hasFlag(flags, FLAG_BG_EXPLICIT) ? rgbToCss(bg) : this.theme.background;
+      if (inverse || hasFlag(flags, FLAG_BG_EXPLICIT)) {
+        ctx.fillStyle = this.colors.background(inverse ? fgColor : bgColor);
+        ctx.fillRect(x * charW, y * charH, width * charW, charH);
       }
     }
 
-    for (let y = 0; y < rows; y++) {
-      for (let x = 0; x < cols; x++) {
-        const base = (y * cols + x) * EXPECTED_CELL_SIZE;
-        const width = view.getUint8(base + 11);
-        if (width === 0) continue;
-        const flags = view.getUint8(base + 10);
-        if (hasFlag(flags, FLAG_INVISIBLE)) continue;
-
-        const codepoint = view.getUint32(base, true);
-        if (codepoint === 0) continue;
-
-        const inverse = hasFlag(flags, FLAG_INVERSE);
-        const fg = readRgb(view, base + 4);
-        const bg = readRgb(view, base + 7);
-        const fgColor = hasFlag(flags, FLAG_FG_EXPLICIT) ? rgbToCss(fg) : this.theme.foreground;
-        const bgColor = hasFlag(flags, FLAG_BG_EXPLICIT) ? rgbToCss(bg) : this.theme.background;
-        ctx.fillStyle = inverse ? bgColor : fgColor;
-
-        let style = "";
-        if (hasFlag(flags, FLAG_ITALIC)) style += "italic ";
-        if (hasFlag(flags, FLAG_BOLD)) style += "bold ";
-        ctx.font = `${style}${this.fontSize}px ${this.fontFamily}`;
-
-        if (hasFlag(flags, FLAG_FAINT)) {
-          ctx.globalAlpha = 0.5;
-        }
+    // Text pass: draw box drawing and block elements procedurally, blit the
+    // rest from the glyph atlas, or batch same-style ASCII runs into one
+    // fillText when there is no atlas or ligatures are wanted.
+    const baselineY = y * charH + this.metrics.baseline;
+    let font = "";
+    let run: TextRun | null = null;
+    const flush = () => {
+      if (!run) return;
+      if (run.font !== font) {
+        font = run.font;
+        ctx.font = font;
+      }
+      ctx.fillStyle = run.color;
+      ctx.globalAlpha = run.faint ? 0.5 : 1;
+      ctx.fillText(run.text, run.x * charW, baselineY);
+      run = null;
+    };
 
-        const text = safeCodepoint(codepoint);
-        ctx.fillText(text, x * charW, y * charH + this.metrics.baseline);
+    for (let x = 0; x < cols; x++) {
+      const base = rowBase + x * EXPECTED_CELL_SIZE;
+      const width = view.getUint8(base + 11);
+      if (width === 0) continue;
+      const flags = view.getUint8(base + 10);
+      const codepoint = view.getUint32(base, true);
+      if (hasFlag(flags, FLAG_INVISIBLE) || codepoint === 0) {
+        flush();
+        continue;
+      }
 
-        if (hasFlag(flags, FLAG_FAINT)) {
-          ctx.globalAlpha = 1;
-        }
-        if (hasFlag(flags, FLAG_UNDERLINE)) {
-          const underlineY = y * charH + this.metrics.baseline + 2;
-          ctx.strokeStyle = ctx.fillStyle;
-          ctx.lineWidth = 1;
-          ctx.beginPath();
-          ctx.moveTo(x * charW, underlineY);
-          ctx.lineTo(x * charW + width * charW, underlineY);
-          ctx.stroke();
+      const inverse = hasFlag(flags, FLAG_INVERSE);
+      const fg = readRgb(view, base + 4);
+      const bg = readRgb(view, base + 7);
+      const fgColor = hasFlag(flags, FLAG_FG_EXPLICIT) ? rgbToCss(fg) : this.theme.foreground;
+      const bgColor = hasFlag(flags, FLAG_BG_EXPLICIT) ? rgbToCss(bg) : this.theme.background;
+      const color = inverse
+        ? this.colors.foreground(bgColor, fgColor)
+        : this.colors.foreground(fgColor, bgColor);
+      const faint = hasFlag(flags, FLAG_FAINT);
+      const style =
+        (hasFlag(flags, FLAG_BOLD) ? GLYPH_BOLD : 0) | (hasFlag(flags, FLAG_ITALIC) ? GLYPH_ITALIC : 0);
+
+      const batchable = this.batchRuns[style] && width === 1 && codepoint >= 0x20 && codepoint < 0x7f;
+      let drawn = false;
+      if (isBoxGlyph(codepoint)) {
+        ctx.globalAlpha = faint ? 0.5 : 1;
+        drawn = drawBoxGlyph(ctx, codepoint, x * charW, y * charH, width * charW, charH, this.dpr, color);
+      } else if (this.atlas && !(batchable && this.font.ligatures)) {
+        ctx.globalAlpha = faint ? 0.5 : 1;
+        drawn = this.atlas.draw(ctx, codepoint, style, color, width, x * charW, y * charH);
+      }
+      if (!drawn) {
+        const cellFont = this.fontFor(style);
+        const text = safeCodepoint(codepoint);
+        if (
+          run &&
+          batchable &&
+          run.end === x &&
+          run.font === cellFont &&
+          run.color === color &&
+          run.faint === faint
+        ) {
+          run.text += text;
+          run.end = x + 1;
+        } else {
+          flush();
+          run = { text, x, end: x + width, font: cellFont, color, faint };
+          if (!batchable) flush();
         }
       }
+
+      if (hasFlag(flags, FLAG_UNDERLINE)) {
+        const underlineY = baselineY + 2;
+        ctx.globalAlpha = faint ? 0.5 : 1;
+        ctx.strokeStyle = color;
+        ctx.lineWidth = 1;
+        ctx.beginPath();
+        ctx.moveTo(x * charW, underlineY);
+        ctx.lineTo(x * charW + width * charW, underlineY);
+        ctx.stroke();
+      }
     }
+    flush();
+    ctx.restore();
   }
 
   dispose() {
@@ -370,16 +710,29 @@ class MiniRenderer {
   }
 
   private measureFont(): FontMetrics {
-    this.ctx.font = `${this.fontSize}px ${this.fontFamily}`;
+    this.ctx.font = this.fontFor(0);
     const metrics = this.ctx.measureText("M");
-    const width = Math.ceil(metrics.width);
     const ascent = metrics.actualBoundingBoxAscent || this.fontSize * 0.8;
     const descent = metrics.actualBoundingBoxDescent || this.fontSize * 0.2;
-    return {
-      width,
-      height: Math.ceil(ascent + descent) + 2,
-      baseline: Math.ceil(ascent) + 1,
-    };
+    return cellMetrics(metrics.width, ascent, descent, this.font);
+  }
+
+  private canBatchRuns(): boolean[] {
+    const onGrid = this.metrics.advance === this.metrics.width || "letterSpacing" in this.ctx;
+    // A separate bold or italic family may advance differently and would drift off the grid.
+    return [0, GLYPH_BOLD, GLYPH_ITALIC, GLYPH_BOLD | GLYPH_ITALIC].map((style) => {
+      if (!onGrid) return false;
+      this.ctx.font = this.fontFor(style);
+      return this.ctx.measureText("M").width === this.metrics.advance;
+    });
+  }
+
+  private createAtlas(): GlyphAtlas | null {
+    return this.glyphCache ? GlyphAtlas.create(this.metrics, this.dpr, (style) => this.fontFor(style)) : null;
+  }
+
+  private fontFor(style: number): string {
+    return cssFont(this.fontSize, this.fontFamily, style, this.font);
   }
 
   private resizeCanvas(cols: number, rows: number) {
@@ -387,53 +740,212 @@ class MiniRenderer {
     const height = rows * this.metrics.height;
     this.canvas.width = Math.max(1, Math.floor(width * this.dpr));
     this.canvas.height = Math.max(1, Math.floor(height * this.dpr));
-    this.canvas.style.width = `${width}px`;
-    this.canvas.style.height = `${height}px`;
+    // A transferred canvas is sized on the page by its owner.
+    if ("style" in this.canvas) {
+      this.canvas.style.width = `${width}px`;
+      this.canvas.style.height = `${height}px`;
+    }
     this.ctx.setTransform(this.dpr, 0, 0, this.dpr, 0, 0);
     this.ctx.textBaseline = "alphabetic";
     this.ctx.textAlign = "left";
+    if (this.metrics.advance !== this.metrics.width && "letterSpacing" in this.ctx) {
+      this.ctx.letterSpacing = `${this.metrics.width - this.metrics.advance}px`;
+    }
+    // Resizing the backing store clears it.
+    this.repaintAll = true;
   }
 }
 
+const GENERIC_FONT_FAMILIES = new Set([
+  "serif",
+  "sans-serif",
+  "monospace",
+  "cursive",
+  "fantasy",
+  "system-ui",
+  "ui-monospace",
+  "emoji",
+  "math",
+]);
+
+/** Copy just the font settings, e.g. to post them to a worker. */
+export function pickFontOptions(options: TerminalFontOptions): TerminalFontOptions {
+  return {
+    fontFamilyBold: options.fontFamilyBold,
+    fontFamilyItalic: options.fontFamilyItalic,
+    fallbackFonts: options.fallbackFonts,
+    lineHeight: options.lineHeight,
+    letterSpacing: options.letterSpacing,
+    ligatures: options.ligatures,
+  };
+}
+
+/** `family` followed by the fallback fonts, quoting bare fallback names. */
+export function fontStack(family: string, fallbackFonts: readonly string[] = []): string {
+  const fallbacks = fallbackFonts
+    .map((name) => name.trim())
+    .filter(Boolean)
+    .map((name) => (/^["']/.test(name) || GENERIC_FONT_FAMILIES.has(name) ? name : JSON.stringify(name)));
+  return [family, ...fallbacks].join(", ");
+}
+
+/** Family stack for a cell style (GLYPH_BOLD / GLYPH_ITALIC bits); italic wins for bold italic. */
+export function fontFamilyFor(fontFamily: string, style: number, font: TerminalFontOptions = {}): string {
+  let family = fontFamily;
+  if (hasFlag(style, GLYPH_ITALIC) && font.fontFamilyItalic) family = font.fontFamilyItalic;
+  else if (hasFlag(style, GLYPH_BOLD) && font.fontFamilyBold) family = font.fontFamilyBold;
+  return fontStack(family, font.fallbackFonts);
+}
+
+/** CSS `font` shorthand for a cell style. */
+export function cssFont(
+  fontSize: number,
+  fontFamily: string,
+  style: number,
+  font: TerminalFontOptions = {}
+): string {
+  const prefix = `${hasFlag(style, GLYPH_ITALIC) ? "italic " : ""}${hasFlag(style, GLYPH_BOLD) ? "bold " : ""}`;
+  return `${prefix}${fontSize}px ${fontFamilyFor(fontFamily, style, font)}`;
+}
+
+/** Default for `fontLoadTimeout`, in milliseconds. */
+export const DEFAULT_FONT_LOAD_TIMEOUT = 3000;
+
+const fontLoads = new Map<string, Promise<void>>();
+
+/** Load the faces the renderer uses (regular, bold, italic); null without the Font Loading API. */
+function loadFontFaces(fontSize: number, fontFamily: string, font: TerminalFontOptions): Promise<void> | null {
+  const scope = globalThis as { document?: { fonts?: FontFaceSet }; fonts?: FontFaceSet };
+  const fonts = scope.document?.fonts ?? scope.fonts;
+  if (!fonts) return null;
+  const shorthands = [0, GLYPH_BOLD, GLYPH_ITALIC, GLYPH_BOLD | GLYPH_ITALIC].map((style) =>
+    cssFont(fontSize, fontFamily, style, font)
+  );
+  const key = shorthands.join("; ");
+  let load = fontLoads.get(key);
+  if (!load) {
+    const faces = shorthands.map((shorthand) => fonts.load(shorthand));
+    load = Promise.allSettled(faces).then(() => undefined);
+    fontLoads.set(key, load);
+  }
+  return load;
+}
+
+/**
+ * Wait for a (web) font before measuring cells with it. Resolves true once
+ * it has loaded, false when `timeoutMs` passes first; call again later (or
+ * use `whenFontLoaded`) to catch a late load.
+ */
+export function loadTerminalFont(
+  fontSize: number,
+  fontFamily: string,
+  timeoutMs = DEFAULT_FONT_LOAD_TIMEOUT,
+  font: TerminalFontOptions = {}
+): Promise<boolean> {
+  const load = loadFontFaces(fontSize, fontFamily, font);
+  if (!load) return Promise.resolve(true);
+  return new Promise((resolve) => {
+    const timer = setTimeout(() => resolve(false), timeoutMs);
+    void load.then(() => {
+      clearTimeout(timer);
+      resolve(true);
+    });
+  });
+}
+
+/** Resolves when the font has loaded, however long that takes. */
+export function whenFontLoaded(
+  fontSize: number,
+  fontFamily: string,
+  font: TerminalFontOptions = {}
+): Promise<void> {
+  return loadFontFaces(fontSize, fontFamily, font) ?? Promise.resolve();
+}
+
+/**
+ * Cell box for a measured "M": letter spacing widens it, line height scales
+ * it, and the baseline moves down by half the added height so glyphs stay
+ * vertically centered.
+ */
+function cellMetrics(
+  advance: number,
+  ascent: number,
+  descent: number,
+  font: TerminalFontOptions
+): FontMetrics {
+  const fontHeight = Math.ceil(ascent + descent) + 2;
+  const height = Math.max(1, Math.round(fontHeight * (font.lineHeight ?? 1)));
+  return {
+    width: Math.max(1, Math.ceil(advance + (font.letterSpacing ?? 0))),
+    height,
+    baseline: Math.ceil(ascent) + 1 + Math.floor((height - fontHeight) / 2),
+    advance,
+  };
+}
+
 /** Measure monospace cell size for a given font. Cheap and synchronous. */
 export function measureCellSize(
   fontSize: number,
-  fontFamily: string
+  fontFamily: string,
+  font: TerminalFontOptions = {}
 ): { w: number; h: number } {
   const canvas = document.createElement("canvas");
   const ctx = canvas.getContext("2d");
   if (!ctx) {
     return {
-      w: Math.ceil(fontSize * 0.6),
-      h: Math.ceil(fontSize * 1.2),
+      w: Math.max(1, Math.ceil(fontSize * 0.6 + (font.letterSpacing ?? 0))),
+      h: Math.max(1, Math.round(Math.ceil(fontSize * 1.2) * (font.lineHeight ?? 1))),
     };
   }
-  ctx.font = `${fontSize}px ${fontFamily}`;
+  ctx.font = cssFont(fontSize, fontFamily, 0, font);
   const m = ctx.measureText("M");
-  const w = Math.ceil(m.width);
   const ascent = m.actualBoundingBoxAscent || fontSize * 0.8;
   const descent = m.actualBoundingBoxDescent || fontSize * 0.2;
-  return { w, h: Math.ceil(ascent + descent) + 2 };
+  const metrics = cellMetrics(m.width, ascent, descent, font);
+  return { w: metrics.width, h: metrics.height };
+}
+
+/**
+ * Call `onChange` with the new `devicePixelRatio` whenever it changes, e.g.
+ * when the window moves to another monitor or the page is zoomed. Returns a
+ * function that stops watching.
+ */
+export function watchDevicePixelRatio(onChange: (dpr: number) => void): () => void {
+  if (typeof window === "undefined" || typeof window.matchMedia !== "function") return () => {};
+  let query: MediaQueryList | null = null;
+  // A resolution query only fires once, when the ratio leaves that value; re-arm it each time.
+  const listen = () => {
+    query = window.matchMedia(`(resolution: ${window.devicePixelRatio}dppx)`);
+    query.addEventListener("change", onQueryChange, { once: true });
+  };
+  const onQueryChange = () => {
+    listen();
+    onChange(window.devicePixelRatio);
+  };
+  listen();
+  return () => query?.removeEventListener("change", onQueryChange);
 }
 
 const runtimeCache = new Map<string, Promise<LibGhosttyRuntime>>();
 
-export function loadLibGhostty(wasmUrl: string | URL = DEFAULT_WASM_URL): Promise<LibGhosttyRuntime> {
-  const key = wasmUrl.toString();
-  const cached = runtimeCache.get(key);
+/**
+ * Load libghostty from a URL, a file path (Node), or the wasm bytes themselves.
+ * URL and path sources are cached for the page/process lifetime.
+ */
+export function loadLibGhostty(
+  wasmSource: string | URL | BufferSource = DEFAULT_WASM_URL
+): Promise<LibGhosttyRuntime> {
+  const key = typeof wasmSource === "string" || wasmSource instanceof URL ? wasmSource.toString() : null;
+  const cached = key !== null ? runtimeCache.get(key) : undefined;
   if (cached) {
     return cached;
   }
 
   const runtimePromise = (async () => {
-    const response = await fetch(wasmUrl);
-    if (!response.ok) {
-      throw new Error(`Failed to load libghostty wasm: ${response.status} ${response.statusText}`);
-    }
-    const bytes = await response.arrayBuffer();
-    if (bytes.byteLength === 0) {
-      throw new Error("libghostty wasm is empty.");
-    }
+    const bytes =
+      typeof wasmSource === "string" || wasmSource instanceof URL
+        ? await loadBytes(wasmSource, "libghostty wasm")
+        : wasmSource;
     const module = await WebAssembly.compile(bytes);
     const instance = await WebAssembly.instantiate(module, {
       env: {
@@ -455,19 +967,26 @@ export function loadLibGhostty(wasmUrl: string | URL = DEFAULT_WASM_URL): Promis
     });
   })();
 
-  runtimeCache.set(key, runtimePromise);
+  if (key !== null) {
+    runtimeCache.set(key, runtimePromise);
+  }
   return runtimePromise;
 }
 
 export async function createMiniTerminalSurface(
   options: MiniTerminalSurfaceOptions
 ): Promise<MiniTerminalSurface> {
-  const runtime = await loadLibGhostty(options.wasmUrl ?? DEFAULT_WASM_URL);
-  const theme: GhosttyTheme = { ...DEFAULT_THEME, ...options.theme };
+  const font = pickFontOptions(options);
+  const colors = pickColorOptions(options);
+  const [runtime, fontLoaded] = await Promise.all([
+    loadLibGhostty(options.wasmUrl ?? DEFAULT_WASM_URL),
+    loadTerminalFont(options.fontSize, options.fontFamily, options.fontLoadTimeout, font),
+  ]);
+  const theme = resolveTheme(options.theme);
 
   let cols: number;
   let rows: number;
-  const cell = measureCellSize(options.fontSize, options.fontFamily);
+  const cell = measureCellSize(options.fontSize, options.fontFamily, font);
   if (options.widthPx != null && options.heightPx != null) {
     cols = Math.max(1, Math.floor(options.widthPx / cell.w));
     rows = Math.max(1, Math.floor(options.heightPx / cell.h));
@@ -478,47 +997,111 @@ export async function createMiniTerminalSurface(
 
   options.container.innerHTML = "";
 
-  const canvas = document.createElement("canvas");
-  canvas.style.display = "block";
-  canvas.style.outline = "none";
-  canvas.tabIndex = options.interactive ? 0 : -1;
-  options.container.appendChild(canvas);
-
   const terminal = runtime.createTerminal(cols, rows, theme);
-  const renderer = new MiniRenderer(
-    canvas,
-    cols,
-    rows,
-    options.fontSize,
-    options.fontFamily,
-    theme
-  );
+  let canvas: HTMLCanvasElement | null = null;
+  let renderer: MiniRenderer | DomRenderer;
+  let element: HTMLElement;
+  if (options.renderer === "dom") {
+    const domRenderer = new DomRenderer(
+      options.container,
+      cols,
+      rows,
+      options.fontSize,
+      options.fontFamily,
+      theme,
+      font,
+      colors
+    );
+    renderer = domRenderer;
+    element = domRenderer.element;
+  } else {
+    canvas = document.createElement("canvas");
+    canvas.style.display = "block";
+    options.container.appendChild(canvas);
+    renderer = new MiniRenderer(
+      canvas,
+      cols,
+      rows,
+      options.fontSize,
+      options.fontFamily,
+      theme,
+      { ...font, ...colors }
+    );
+    element = canvas;
+  }
+  element.style.outline = "none";
+  element.tabIndex = options.interactive ? 0 : -1;
+  const liveRegion = new LiveRegion(options.container, options.announce ?? "rows");
 
   if (!options.showCursor) {
     terminal.write("\x1b[?25l");
   }
-  renderer.render(terminal.getViewportData());
+  const paint = () => {
+    const { viewport, rows: dirtyRows } = terminal.takeDirtyRows();
+    renderer.render(viewport, dirtyRows);
+    liveRegion.update(viewport, dirtyRows);
+  };
+  paint();
 
   const requestFrame =
     window.requestAnimationFrame?.bind(window) ?? ((cb: FrameRequestCallback) => window.setTimeout(cb, 16));
   const cancelFrame = window.cancelAnimationFrame?.bind(window) ?? window.clearTimeout.bind(window);
   let frameId: number | null = null;
   let disposed = false;
+  let suspended = false;
   const renderFrame = () => {
     frameId = null;
     if (disposed || !terminal.isDirty()) return;
-    renderer.render(terminal.getViewportData());
+    paint();
   };
   const scheduleRender = () => {
-    if (frameId !== null || disposed) return;
+    if (frameId !== null || disposed || suspended) return;
     frameId = requestFrame(renderFrame) as number;
   };
 
+  // The DOM renderer has no pixels of its own; screenshots paint a detached canvas.
+  const paintDetachedCanvas = (viewport: ViewportData) => {
+    const detached = document.createElement("canvas");
+    new MiniRenderer(
+      detached,
+      viewport.cols,
+      viewport.rows,
+      options.fontSize,
+      options.fontFamily,
+      theme,
+      { ...font, ...colors }
+    ).render(viewport);
+    return detached;
+  };
+
   const detachInput = options.interactive
-    ? attachBasicInput(canvas, (data) => options.onInput?.(data))
+    ? attachBasicInput(element, (data) => options.onInput?.(data))
     : () => {};
 
-  return {
+  // Only the canvas needs rescaling; the browser re-renders DOM text itself.
+  const canvasRenderer = renderer instanceof MiniRenderer ? renderer : null;
+  const stopWatchingDpr = canvasRenderer
+    ? watchDevicePixelRatio((dpr) => {
+        if (disposed) return;
+        canvasRenderer.setDevicePixelRatio(dpr);
+        surface.cellSize = canvasRenderer.cellSize;
+        paint();
+        options.onCellSizeChange?.(surface.cellSize);
+      })
+    : () => {};
+
+  // Measured with a fallback font; re-measure once the real one arrives.
+  if (!fontLoaded) {
+    void whenFontLoaded(options.fontSize, options.fontFamily, font).then(() => {
+      if (disposed) return;
+      renderer.remeasureFont();
+      surface.cellSize = renderer.cellSize;
+      paint();
+      options.onCellSizeChange?.(surface.cellSize);
+    });
+  }
+
+  const surface: MiniTerminalSurface = {
     cols,
     rows,
     cellSize: renderer.cellSize,
@@ -526,17 +1109,62 @@ export async function createMiniTerminalSurface(
       if (disposed) return;
       const normalized = options.convertEol ? normalizeEol(text) : text;
       terminal.write(normalized);
+      if (options.onResponse) {
+        for (const response of terminal.drainResponses()) options.onResponse(response);
+      }
+      if (options.onTitle) {
+        const title = terminal.takeTitle();
+        if (title !== null) options.onTitle(title);
+      }
+      scheduleRender();
+    },
+    resize(nextCols: number, nextRows: number) {
+      if (disposed) return;
+      const safeCols = Math.max(1, Math.floor(nextCols));
+      const safeRows = Math.max(1, Math.floor(nextRows));
+      terminal.resize(safeCols, safeRows);
+      surface.cols = safeCols;
+      surface.rows = safeRows;
       scheduleRender();
     },
+    setSuspended(next: boolean) {
+      if (suspended === next) return;
+      suspended = next;
+      if (suspended && frameId !== null) {
+        cancelFrame(frameId);
+        frameId = null;
+      }
+      if (!suspended) scheduleRender();
+    },
+    getText() {
+      if (disposed) return "";
+      return viewportToText(terminal.getViewportData(false));
+    },
+    getViewportData() {
+      if (disposed) return { cols: surface.cols, rows: surface.rows, buffer: new Uint8Array(0) };
+      return terminal.getViewportData(false);
+    },
+    getState() {
+      if (disposed) return null;
+      return terminal.getState();
+    },
+    toPng() {
+      if (disposed) return Promise.reject(new Error("Terminal surface has been disposed."));
+      if (terminal.isDirty()) paint();
+      const source = canvas ?? paintDetachedCanvas(terminal.getViewportData(false));
+      return new Promise<Blob>((resolve, reject) => {
+        source.toBlob((blob) => {
+          if (blob) resolve(blob);
+          else reject(new Error("Failed to encode canvas as PNG."));
+        }, "image/png");
+      });
+    },
+    focus() {
+      element.focus();
+    },
     drainResponses() {
       if (disposed) return [];
-      const responses: string[] = [];
-      while (terminal.hasResponse()) {
-        const response = terminal.readResponse();
-        if (!response) break;
-        responses.push(response);
-      }
-      return responses;
+      return terminal.drainResponses();
     },
     dispose() {
       disposed = true;
@@ -545,13 +1173,16 @@ export async function createMiniTerminalSurface(
         frameId = null;
       }
       detachInput();
+      stopWatchingDpr();
       renderer.dispose();
+      liveRegion.dispose();
       terminal.dispose();
-      if (canvas.parentElement === options.container) {
+      if (canvas?.parentElement === options.container) {
         options.container.removeChild(canvas);
       }
     },
   };
+  return surface;
 }
 
 function assertAbiCompatibility(wasm: LibGhosttyExports): void {
@@ -632,11 +1263,145 @@ function assertAbiCompatibility(wasm: LibGhosttyExports): void {
   }
 }
 
-function normalizeEol(text: string): string {
+/** Fill in a partial theme with the built-in defaults. */
+export function resolveTheme(theme?: Partial<GhosttyTheme>): GhosttyTheme {
+  return { ...DEFAULT_THEME, ...theme };
+}
+
+/** A run of adjacent cells sharing one style, with colors resolved against a theme. */
+export interface StyledSpan {
+  text: string;
+  /** First column of the span */
+  col: number;
+  /** Columns covered (wide characters count twice) */
+  width: number;
+  fg: string;
+  /** Null when the cell uses the theme background */
+  bg: string | null;
+  bold: boolean;
+  italic: boolean;
+  underline: boolean;
+  faint: boolean;
+}
+
+function sameStyle(a: StyledSpan, b: StyledSpan): boolean {
+  return (
+    a.fg === b.fg &&
+    a.bg === b.bg &&
+    a.bold === b.bold &&
+    a.italic === b.italic &&
+    a.underline === b.underline &&
+    a.faint === b.faint
+  );
+}
+
+/** Append a span to a line, merging it into the previous span when styles match. */
+export function pushStyledSpan(line: StyledSpan[], span: StyledSpan) {
+  const last = line[line.length - 1];
+  if (last && last.col + last.width === span.col && sameStyle(last, span)) {
+    last.text += span.text;
+    last.width += span.width;
+  } else {
+    line.push(span);
+  }
+}
+
+/** Drop trailing unstyled whitespace from a line of spans. */
+export function trimStyledLine(line: StyledSpan[]): StyledSpan[] {
+  while (line.length > 0) {
+    const last = line[line.length - 1];
+    if (last.bg !== null || last.underline) break;
+    const trimmed = last.text.replace(/\s+$/, "");
+    if (trimmed) {
+      last.width -= last.text.length - trimmed.length;
+      last.text = trimmed;
+      break;
+    }
+    line.pop();
+  }
+  return line;
+}
+
+/** Styled spans per row, resolving inverse and default colors the way the renderer does. */
+export function viewportToSpans(viewport: ViewportData, theme: GhosttyTheme): StyledSpan[][] {
+  const lines: StyledSpan[][] = [];
+  for (let y = 0; y < viewport.rows; y++) {
+    lines.push(viewportRowToSpans(viewport, y, theme));
+  }
+  return lines;
+}
+
+/** Styled spans of one viewport row; see viewportToSpans. */
+export function viewportRowToSpans(viewport: ViewportData, y: number, theme: GhosttyTheme): StyledSpan[] {
+  const { cols, buffer } = viewport;
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  const line: StyledSpan[] = [];
+  for (let x = 0; x < cols; x++) {
+    const base = (y * cols + x) * EXPECTED_CELL_SIZE;
+    if (base + EXPECTED_CELL_SIZE > buffer.byteLength) break;
+    const width = cellColumns(view, base, x);
+    if (width === 0) continue;
+    const flags = view.getUint8(base + 10);
+    const codepoint = view.getUint32(base, true);
+    const fgExplicit = hasFlag(flags, FLAG_FG_EXPLICIT);
+    const bgExplicit = hasFlag(flags, FLAG_BG_EXPLICIT);
+    const fgColor = fgExplicit ? rgbToCss(readRgb(view, base + 4)) : theme.foreground;
+    const bgColor = bgExplicit ? rgbToCss(readRgb(view, base + 7)) : null;
+    const inverse = hasFlag(flags, FLAG_INVERSE);
+    pushStyledSpan(line, {
+      text: codepoint === 0 || hasFlag(flags, FLAG_INVISIBLE) ? " " : safeCodepoint(codepoint),
+      col: x,
+      width,
+      fg: inverse ? (bgColor ?? theme.background) : fgColor,
+      bg: inverse ? fgColor : bgColor,
+      bold: hasFlag(flags, FLAG_BOLD),
+      italic: hasFlag(flags, FLAG_ITALIC),
+      underline: hasFlag(flags, FLAG_UNDERLINE),
+      faint: hasFlag(flags, FLAG_FAINT),
+    });
+  }
+  return trimStyledLine(line);
+}
+
+/**
+ * Columns a cell covers. Never-written cells encode width 0 just like the
+ * spacer after a wide character; only the spacer covers 0 columns.
+ */
+export function cellColumns(view: DataView, base: number, x: number): number {
+  const width = view.getUint8(base + 11);
+  if (width > 0) return width;
+  return x > 0 && view.getUint8(base - EXPECTED_CELL_SIZE + 11) === 2 ? 0 : 1;
+}
+
+/** Convert a viewport cell buffer to plain text, trimming trailing spaces per row. */
+export function viewportToText(viewport: ViewportData): string {
+  const lines: string[] = [];
+  for (let y = 0; y < viewport.rows; y++) {
+    lines.push(viewportRowText(viewport, y));
+  }
+  return lines.join("\n");
+}
+
+/** Plain text of one viewport row, trailing spaces trimmed. */
+export function viewportRowText(viewport: ViewportData, y: number): string {
+  const { cols, buffer } = viewport;
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  let line = "";
+  for (let x = 0; x < cols; x++) {
+    const base = (y * cols + x) * EXPECTED_CELL_SIZE;
+    if (base + EXPECTED_CELL_SIZE > buffer.byteLength) break;
+    if (cellColumns(view, base, x) === 0) continue;
+    const codepoint = view.getUint32(base, true);
+    line += codepoint === 0 ? " " : safeCodepoint(codepoint);
+  }
+  return line.replace(/ +$/, "");
+}
+
+export function normalizeEol(text: string): string {
   return text.replace(/\r?\n/g, "\r\n");
 }
 
-function parseColorToHex(color: string): number {
+export function parseColorToHex(color: string): number {
   if (color.startsWith("#")) {
     let hex = color.slice(1);
     if (hex.length === 3) {
@@ -657,7 +1422,7 @@ function unpackHexColor(hex: number): [number, number, number] {
   return [(hex >> 16) & 0xff, (hex >> 8) & 0xff, hex & 0xff];
 }
 
-function readRgb(view: DataView, offset: number): { r: number; g: number; b: number } {
+function readRgb(view: DataView, offset: number): RgbColor {
   return {
     r: view.getUint8(offset),
     g: view.getUint8(offset + 1),
@@ -669,7 +1434,7 @@ function hasFlag(flags: number, bit: number): boolean {
   return (flags & bit) !== 0;
 }
 
-function rgbToCss(rgb: { r: number; g: number; b: number }): string {
+function rgbToCss(rgb: RgbColor): string {
   return `rgb(${rgb.r}, ${rgb.g}, ${rgb.b})`;
 }
 
@@ -681,7 +1446,8 @@ function safeCodepoint(codepoint: number): string {
   }
 }
 
-function attachBasicInput(target: HTMLElement, onInput: (data: string) => void) {
+/** Forward keys and pastes on `target` as terminal input; returns a detach function. */
+export function attachBasicInput(target: HTMLElement, onInput: (data: string) => void) {
   const onMouseDown = () => target.focus();
   const onPaste = (event: ClipboardEvent) => {
     const text = event.clipboardData?.getData("text");
@@ -707,64 +1473,119 @@ function attachBasicInput(target: HTMLElement, onInput: (data: string) => void)
   };
 }
 
+/** Escape sequences for non-printable keys, keyed by `KeyboardEvent.key`. */
+const KEY_SEQUENCES: Record<string, string> = {
+  Enter: "\r",
+  Backspace: "\x7f",
+  Tab: "\t",
+  Escape: "\x1b",
+  ArrowUp: "\x1b[A",
+  ArrowDown: "\x1b[B",
+  ArrowRight: "\x1b[C",
+  ArrowLeft: "\x1b[D",
+  Home: "\x1b[H",
+  End: "\x1b[F",
+  Delete: "\x1b[3~",
+  PageUp: "\x1b[5~",
+  PageDown: "\x1b[6~",
+};
+
+/** Short aliases accepted by encodeKey in addition to `KeyboardEvent.key` names. */
+const KEY_ALIASES: Record<string, string> = {
+  up: "ArrowUp",
+  down: "ArrowDown",
+  left: "ArrowLeft",
+  right: "ArrowRight",
+  esc: "Escape",
+  return: "Enter",
+  space: " ",
+  del: "Delete",
+  pgup: "PageUp",
+  pgdn: "PageDown",
+};
+
 function encodeKeyboardEvent(event: KeyboardEvent): string | null {
   if (event.isComposing || event.metaKey) return null;
+  return encodeKeyWithModifiers(event.key, event.ctrlKey, event.altKey, event.shiftKey);
+}
+
+/**
+ * Encode a named key such as `"Enter"`, `"Down"`, `"Ctrl+C"` or `"Alt+x"` as
+ * the bytes a terminal would send. Returns null for unknown keys.
+ */
+export function encodeKey(name: string): string | null {
+  const parts = name.split("+");
+  let key = parts.pop() ?? "";
+  if (key === "" && name.endsWith("+")) key = "+";
+  let ctrl = false;
+  let alt = false;
+  let shift = false;
+  for (const modifier of parts) {
+    switch (modifier.toLowerCase()) {
+      case "ctrl":
+      case "control":
+        ctrl = true;
+        break;
+      case "alt":
+      case "meta":
+        alt = true;
+        break;
+      case "shift":
+        shift = true;
+        break;
+      case "":
+        break;
+      default:
+        return null;
+    }
+  }
+  const lower = key.toLowerCase();
+  let canonical = Object.hasOwn(KEY_ALIASES, lower)
+    ? KEY_ALIASES[lower]
+    : (Object.keys(KEY_SEQUENCES).find((known) => known.toLowerCase() === lower) ?? key);
+  if (shift && canonical.length === 1) {
+    canonical = canonical.toUpperCase();
+  }
+  return encodeKeyWithModifiers(canonical, ctrl, alt, shift);
+}
 
+/**
+ * Encode every key in `keys` with encodeKey, throwing on the first unknown
+ * one before anything is returned, so callers never send half a sequence.
+ */
+export function encodeKeys(keys: readonly string[]): string[] {
+  return keys.map((key) => {
+    const encoded = encodeKey(key);
+    if (encoded === null) {
+      throw new Error(`Unknown key: ${key}`);
+    }
+    return encoded;
+  });
+}
+
+function encodeKeyWithModifiers(
+  key: string,
+  ctrl: boolean,
+  alt: boolean,
+  shift: boolean
+): string | null {
   let value: string | null = null;
-  switch (event.key) {
-    case "Enter":
-      value = "\r";
-      break;
-    case "Backspace":
-      value = "\x7f";
-      break;
-    case "Tab":
-      value = event.shiftKey ? "\x1b[Z" : "\t";
-      break;
-    case "Escape":
-      value = "\x1b";
-      break;
-    case "ArrowUp":
-      value = "\x1b[A";
-      break;
-    case "ArrowDown":
-      value = "\x1b[B";
-      break;
-    case "ArrowRight":
-      value = "\x1b[C";
-      break;
-    case "ArrowLeft":
-      value = "\x1b[D";
-      break;
-    case "Home":
-      value = "\x1b[H";
-      break;
-    case "End":
-      value = "\x1b[F";
-      break;
-    case "Delete":
-      value = "\x1b[3~";
-      break;
-    case "PageUp":
-      value = "\x1b[5~";
-      break;
-    case "PageDown":
-      value = "\x1b[6~";
-      break;
-    default:
-      break;
+  if (key === "Tab" && shift) {
+    value = "\x1b[Z";
+  } else if (Object.hasOwn(KEY_SEQUENCES, key)) {
+    value = KEY_SEQUENCES[key];
   }
 
-  if (!value && event.ctrlKey) {
-    value = encodeCtrlKey(event.key);
+  if (!value && ctrl) {
+    value = encodeCtrlKey(key);
   }
 
-  if (!value && event.key.length === 1 && !event.ctrlKey) {
-    value = event.key;
+  if (!value && key.length === 1 && !ctrl) {
+    value = key;
   }
 
   if (!value) return null;
-  if (event.altKey && !value.startsWith("\x1b")) {
+  if (alt && !value.startsWith("\x1b")) {
     return `\x1b${value}`;
   }
   return value;
@@ -0,0 +1,96 @@
+/**
+ * Screen reader announcements: a visually hidden live region that reads out
+ * rows whose text changed since they were last announced.
+ */
+
+import type { TuiAnnounceVerbosity } from "../types.js";
+import { viewportRowText, type ViewportData } from "./libghostty.js";
+
+/** Changes are collected for this long, so a redraw is announced once. */
+const ANNOUNCE_DELAY_MS = 300;
+/** Older announcements are pruned from the log. */
+const MAX_MESSAGES = 20;
+const MAX_MESSAGE_LENGTH = 2000;
+
+const VISUALLY_HIDDEN: Partial<CSSStyleDeclaration> = {
+  position: "absolute",
+  width: "1px",
+  height: "1px",
+  margin: "-1px",
+  padding: "0",
+  border: "0",
+  overflow: "hidden",
+  clipPath: "inset(50%)",
+  whiteSpace: "nowrap",
+};
+
+/** Box drawing, block elements and braille read as noise; announce them as spaces. */
+function speakable(text: string): string {
+  return text.replace(/[\u2500-\u259f\u2800-\u28ff]/g, " ").replace(/\s+/g, " ").trim();
+}
+
+export class LiveRegion {
+  readonly element: HTMLDivElement;
+  private rowText: string[] = [];
+  /** Rows changed since the last announcement, with their new text. */
+  private readonly changed = new Map<number, string>();
+  private timer: ReturnType<typeof setTimeout> | null = null;
+
+  constructor(container: HTMLElement, private readonly verbosity: TuiAnnounceVerbosity) {
+    this.element = document.createElement("div");
+    this.element.setAttribute("role", "log");
+    this.element.setAttribute("aria-live", "polite");
+    Object.assign(this.element.style, VISUALLY_HIDDEN);
+    container.appendChild(this.element);
+  }
+
+  /** Note the rows of `viewport` that may have changed; every row when `rows` is omitted. */
+  update(viewport: ViewportData, rows?: readonly number[]) {
+    if (this.verbosity === "off") return;
+    if (this.rowText.length !== viewport.rows) {
+      this.rowText = new Array<string>(viewport.rows).fill("");
+      this.changed.clear();
+      rows = undefined;
+    }
+    const check = (y: number) => {
+      const text = speakable(viewportRowText(viewport, y));
+      if (text === this.rowText[y]) return;
+      this.rowText[y] = text;
+      if (text) this.changed.set(y, text);
+      else this.changed.delete(y);
+    };
+    if (rows) {
+      for (const y of rows) {
+        if (y < viewport.rows) check(y);
+      }
+    } else {
+      for (let y = 0; y < viewport.rows; y++) check(y);
+    }
+    if (this.changed.size > 0 && this.timer === null) {
+      this.timer = setTimeout(() => this.announce(), ANNOUNCE_DELAY_MS);
+    }
+  }
+
+  dispose() {
+    if (this.timer !== null) clearTimeout(this.timer);
+    this.timer = null;
+    this.element.remove();
+  }
+
+  private announce() {
+    this.timer = null;
+    const lines = [...this.changed.entries()].sort(([a], [b]) => a - b).map(([, text]) => text);
+    this.changed.clear();
+    if (lines.length === 0) return;
+    let message = (this.verbosity === "line" ? lines.slice(-1) : lines).join("\n");
+    if (message.length > MAX_MESSAGE_LENGTH) {
+      message = `${message.slice(0, MAX_MESSAGE_LENGTH)}…`;
+    }
+    const entry = document.createElement("div");
+    entry.textContent = message;
+    this.element.appendChild(entry);
+    while (this.element.childElementCount > MAX_MESSAGES) {
+      this.element.firstElementChild?.remove();
+    }
+  }
+}
@@ -0,0 +1,45 @@
+/**
+ * Byte loading shared by the app, libghostty and asciicast loaders: `fetch` in the
+ * browser, the filesystem for `file:` URLs and plain paths under Node.
+ */
+
+const nodeProcess = (globalThis as { process?: { versions?: { node?: string } } }).process;
+const isNode = nodeProcess?.versions?.node !== undefined;
+
+/** Kept out of the import graph so bundlers don't try to resolve it for the browser. */
+const NODE_FS_MODULE = "node:fs/promises";
+
+interface NodeFs {
+  readFile(path: string | URL): Promise<Uint8Array>;
+}
+
+/** `file:` URL, or (under Node) a string without a URL scheme; Windows drive paths count as paths. */
+function filePathOf(source: string | URL): string | URL | null {
+  if (source instanceof URL) return source.protocol === "file:" ? source : null;
+  if (source.startsWith("file:")) return new URL(source);
+  if (isNode && (!/^[a-z][a-z0-9+.-]*:/i.test(source) || /^[a-z]:[\\/]/i.test(source))) {
+    return source;
+  }
+  return null;
+}
+
+/** Fetch `source` as bytes; `label` names it in error messages. */
+export async function loadBytes(source: string | URL, label: string): Promise<ArrayBuffer> {
+  let bytes: ArrayBuffer;
+  const path = filePathOf(source);
+  if (path !== null) {
+    const { readFile } = (await import(/* @vite-ignore */ NODE_FS_MODULE)) as NodeFs;
+    const file = await readFile(path);
+    bytes = file.buffer.slice(file.byteOffset, file.byteOffset + file.byteLength) as ArrayBuffer;
+  } else {
+    const response = await fetch(source);
+    if (!response.ok) {
+      throw new Error(`Failed to load ${label}: ${response.status} ${response.statusText}`);
+    }
+    bytes = await response.arrayBuffer();
+  }
+  if (bytes.byteLength === 0) {
+    throw new Error(`${label[0].toUpperCase()}${label.slice(1)} is empty.`);
+  }
+  return bytes;
+}
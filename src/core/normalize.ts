@@ -1,10 +1,15 @@
 import type {
   ResolvedTuiPreviewOptions,
   TuiArgv,
+  TuiDeterminismOptions,
+  TuiFitMode,
   TuiPreviewProps,
   TuiRenderMode,
   TuiRuntimeSize,
+  TuiScriptOptions,
+  TuiScriptStep,
 } from "../types.js";
+import { DEFAULT_FONT_LOAD_TIMEOUT } from "./libghostty.js";
 
 const DEFAULT_SIZE: TuiRuntimeSize = { cols: 80, rows: 24 };
 const EMPTY_ENV: Record<string, string> = {};
@@ -17,6 +22,34 @@ function normalizeMode(mode: TuiRenderMode | undefined): ResolvedTuiPreviewOptio
   return "interactive";
 }
 
+function resolveDeterminism(
+  determinism: boolean | TuiDeterminismOptions | undefined
+): ResolvedTuiPreviewOptions["determinism"] {
+  if (!determinism) {
+    return null;
+  }
+  const options = determinism === true ? {} : determinism;
+  return {
+    seed: options.seed ?? 1,
+    epochMs: options.epochMs ?? 0,
+  };
+}
+
+function resolveScript(
+  script: TuiScriptStep[] | TuiScriptOptions | undefined
+): ResolvedTuiPreviewOptions["script"] {
+  if (!script) {
+    return null;
+  }
+  const options = Array.isArray(script) ? { steps: script } : script;
+  return {
+    steps: options.steps,
+    loop: options.loop ?? false,
+    handOffOnInput: options.handOffOnInput ?? true,
+    typingDelay: options.typingDelay ?? 50,
+  };
+}
+
 function resolveArgvInput(argv: TuiArgv | undefined): (size: TuiRuntimeSize) => string[] {
   const value = argv ?? EMPTY_ARGV;
   if (typeof value === "function") {
@@ -36,22 +69,97 @@ export function resolveTuiPreviewProps(
   const mode = normalizeMode(props.mode);
 
   return {
-    wasm: props.wasm,
+    wasm: props.wasm ?? null,
+    cast: props.cast ?? null,
+    playback: {
+      autoplay: props.playback?.autoplay ?? true,
+      speed: props.playback?.speed ?? 1,
+      loop: props.playback?.loop ?? false,
+      idleTimeLimit: props.playback?.idleTimeLimit,
+      controls: props.playback?.controls ?? true,
+    },
     env: props.env ?? EMPTY_ENV,
+    determinism: resolveDeterminism(props.determinism),
+    stdin: props.stdin ?? null,
+    closeStdin: props.closeStdin ?? false,
+    script: resolveScript(props.script),
     interactive: mode === "static" ? false : (props.interactive ?? true),
+    start: props.start ?? "eager",
+    poster: props.poster ?? null,
+    session: props.session ?? null,
     mode,
     fit,
     size,
     terminal: {
       fontSize: props.terminal?.fontSize ?? 14,
       fontFamily: props.terminal?.fontFamily ?? "monospace",
+      fontFamilyBold: props.terminal?.fontFamilyBold,
+      fontFamilyItalic: props.terminal?.fontFamilyItalic,
+      fallbackFonts: props.terminal?.fallbackFonts ?? [],
+      lineHeight: props.terminal?.lineHeight ?? 1,
+      letterSpacing: props.terminal?.letterSpacing ?? 0,
+      ligatures: props.terminal?.ligatures ?? false,
+      minimumContrastRatio: props.terminal?.minimumContrastRatio ?? 1,
+      daltonize: props.terminal?.daltonize ?? "none",
       wasmUrl: props.terminal?.wasmUrl,
       convertEol: props.terminal?.convertEol ?? true,
+      worker: props.terminal?.worker ?? false,
+      renderer: props.terminal?.renderer ?? "canvas",
+      announce: props.terminal?.announce ?? "rows",
+      fontLoadTimeout: props.terminal?.fontLoadTimeout ?? DEFAULT_FONT_LOAD_TIMEOUT,
       theme: props.terminal?.theme,
     },
     resolveArgv: resolveArgvInput(props.argv),
+    stderr: props.stderr ?? "terminal",
+    record: props.record ?? false,
+    onRecordingComplete: props.onRecordingComplete,
+    onStdout: props.onStdout,
+    onStderr: props.onStderr,
     onExit: props.onExit,
     onError: props.onError,
     onStatusChange: props.onStatusChange,
+    onTitleChange: props.onTitleChange,
   };
 }
+
+/** Grid size a TuiPreview runs at; null until a container fit has measured it. */
+export interface TermSizeState {
+  size: TuiRuntimeSize | null;
+  /** Whether container measurements still change `size`. */
+  fitting: boolean;
+}
+
+export type TermSizeAction =
+  | { type: "reset"; fit: TuiFitMode; size: TuiRuntimeSize }
+  | { type: "fit"; size: TuiRuntimeSize }
+  | { type: "resize"; size: TuiRuntimeSize };
+
+function sameSize(a: TuiRuntimeSize | null, b: TuiRuntimeSize): boolean {
+  return a !== null && a.cols === b.cols && a.rows === b.rows;
+}
+
+/**
+ * `reset` follows the `fit`/`size` props, `fit` is a container measurement
+ * and `resize` an imperative resize(), which stops container fitting until
+ * the next reset. Unchanged sizes return `state` itself, so effects keyed
+ * on the size don't rerun.
+ */
+export function reduceTermSize(state: TermSizeState, action: TermSizeAction): TermSizeState {
+  switch (action.type) {
+    case "reset":
+      if (action.fit === "container") {
+        return state.fitting ? state : { size: null, fitting: true };
+      }
+      return !state.fitting && sameSize(state.size, action.size)
+        ? state
+        : { size: action.size, fitting: false };
+    case "fit":
+      return !state.fitting || sameSize(state.size, action.size)
+        ? state
+        : { size: action.size, fitting: true };
+    case "resize": {
+      const size = { cols: Math.max(1, action.size.cols), rows: Math.max(1, action.size.rows) };
+      return !state.fitting && sameSize(state.size, size) ? state : { size, fitting: false };
+    }
+  }
+}
@@ -0,0 +1,286 @@
+/**
+ * Poster frames — a precomputed snapshot of the first screen, rendered as
+ * static HTML (including during SSR) until the live canvas takes over.
+ */
+
+import type { GhosttyTheme, TuiDeterminismOptions, TuiPosterSnapshot } from "../types.js";
+import { decodeBase64, encodeBase64 } from "./base64.js";
+import { createHeadlessTerminal } from "./headless.js";
+import {
+  ANSI_THEME_KEYS,
+  pushStyledSpan,
+  trimStyledLine,
+  viewportToSpans,
+  type StyledSpan,
+  type ViewportData,
+} from "./libghostty.js";
+import { WasiBridge, hasJSPI, instantiateApp } from "./wasi.js";
+
+const TAB_WIDTH = 8;
+
+export function encodeCellBuffer(buffer: Uint8Array): string {
+  return encodeBase64(buffer);
+}
+
+export function decodeCellBuffer(cells: string): Uint8Array {
+  return decodeBase64(cells);
+}
+
+/** Styled spans per row for a poster snapshot. */
+export function posterToSpans(poster: TuiPosterSnapshot, theme: GhosttyTheme): StyledSpan[][] {
+  if ("cells" in poster) {
+    const viewport: ViewportData = {
+      cols: poster.cols,
+      rows: poster.rows,
+      buffer: decodeCellBuffer(poster.cells),
+    };
+    return viewportToSpans(viewport, theme);
+  }
+  return ansiToSpans(poster.ansi, theme, poster);
+}
+
+/** xterm 256-color palette entry, using the theme for the first 16. */
+function paletteColor(index: number, theme: GhosttyTheme): string {
+  if (index < 16) return theme[ANSI_THEME_KEYS[index]];
+  if (index < 232) {
+    const level = (n: number) => (n === 0 ? 0 : 55 + n * 40);
+    const i = index - 16;
+    return `rgb(${level(Math.floor(i / 36))}, ${level(Math.floor(i / 6) % 6)}, ${level(i % 6)})`;
+  }
+  const gray = 8 + (index - 232) * 10;
+  return `rgb(${gray}, ${gray}, ${gray})`;
+}
+
+interface SgrState {
+  fg: string | null;
+  bg: string | null;
+  bold: boolean;
+  italic: boolean;
+  underline: boolean;
+  faint: boolean;
+  inverse: boolean;
+  hidden: boolean;
+}
+
+const EMPTY_SGR: SgrState = {
+  fg: null,
+  bg: null,
+  bold: false,
+  italic: false,
+  underline: false,
+  faint: false,
+  inverse: false,
+  hidden: false,
+};
+
+/** Parse an extended color (`5;n` or `2;r;g;b`) starting at `params[i]`; returns [color, consumed]. */
+function readExtendedColor(params: number[], i: number, theme: GhosttyTheme): [string | null, number] {
+  if (params[i] === 5 && params.length > i + 1) {
+    return [paletteColor(params[i + 1] & 0xff, theme), 2];
+  }
+  if (params[i] === 2 && params.length > i + 3) {
+    return [`rgb(${params[i + 1]}, ${params[i + 2]}, ${params[i + 3]})`, 4];
+  }
+  return [null, params.length - i];
+}
+
+function applySgr(state: SgrState, params: number[], theme: GhosttyTheme): SgrState {
+  let next = { ...state };
+  if (params.length === 0) params = [0];
+  for (let i = 0; i < params.length; i++) {
+    const p = params[i];
+    if (p === 0) next = { ...EMPTY_SGR };
+    else if (p === 1) next.bold = true;
+    else if (p === 2) next.faint = true;
+    else if (p === 3) next.italic = true;
+    else if (p === 4) next.underline = true;
+    else if (p === 7) next.inverse = true;
+    else if (p === 8) next.hidden = true;
+    else if (p === 22) next.bold = next.faint = false;
+    else if (p === 23) next.italic = false;
+    else if (p === 24) next.underline = false;
+    else if (p === 27) next.inverse = false;
+    else if (p === 28) next.hidden = false;
+    else if (p >= 30 && p <= 37) next.fg = paletteColor(p - 30, theme);
+    else if (p >= 90 && p <= 97) next.fg = paletteColor(p - 90 + 8, theme);
+    else if (p >= 40 && p <= 47) next.bg = paletteColor(p - 40, theme);
+    else if (p >= 100 && p <= 107) next.bg = paletteColor(p - 100 + 8, theme);
+    else if (p === 39) next.fg = null;
+    else if (p === 49) next.bg = null;
+    else if (p === 38 || p === 48) {
+      const [color, consumed] = readExtendedColor(params, i + 1, theme);
+      if (p === 38) next.fg = color;
+      else next.bg = color;
+      i += consumed;
+    }
+  }
+  return next;
+}
+
+/**
+ * Styled spans from ANSI text. Only newlines, tabs and SGR styling are
+ * interpreted; other escape sequences (cursor movement, modes) are dropped.
+ */
+export function ansiToSpans(
+  ansi: string,
+  theme: GhosttyTheme,
+  size: { cols?: number; rows?: number } = {}
+): StyledSpan[][] {
+  const lines: StyledSpan[][] = [[]];
+  let column = 0;
+  let state = { ...EMPTY_SGR };
+
+  const emit = (text: string) => {
+    if (size.cols !== undefined) {
+      text = text.slice(0, Math.max(0, size.cols - column));
+    }
+    if (!text) return;
+    const col = column;
+    column += text.length;
+    const fg = state.fg ?? theme.foreground;
+    pushStyledSpan(lines[lines.length - 1], {
+      text: state.hidden ? " ".repeat(text.length) : text,
+      col,
+      width: text.length,
+      fg: state.inverse ? (state.bg ?? theme.background) : fg,
+      bg: state.inverse ? fg : state.bg,
+      bold: state.bold,
+      italic: state.italic,
+      underline: state.underline,
+      faint: state.faint,
+    });
+  };
+
+  let i = 0;
+  while (i < ansi.length) {
+    const ch = ansi[i];
+    if (ch === "\x1b") {
+      const kind = ansi[i + 1];
+      if (kind === "[") {
+        let end = i + 2;
+        while (end < ansi.length && !/[\x40-\x7e]/.test(ansi[end])) end++;
+        if (ansi[end] === "m") {
+          const params = ansi
+            .slice(i + 2, end)
+            .split(/[;:]/)
+            .filter((part) => part !== "")
+            .map(Number);
+          state = applySgr(state, params, theme);
+        }
+        i = end + 1;
+      } else if (kind === "]") {
+        const bel = ansi.indexOf("\x07", i);
+        const st = ansi.indexOf("\x1b\\", i);
+        const ends = [bel === -1 ? Infinity : bel + 1, st === -1 ? Infinity : st + 2];
+        i = Math.min(...ends, ansi.length);
+      } else if (kind !== undefined && "()*+".includes(kind)) {
+        i += 3; // charset designation
+      } else {
+        i += 2;
+      }
+      continue;
+    }
+    if (ch === "\n") {
+      lines.push([]);
+      column = 0;
+    } else if (ch === "\t") {
+      emit(" ".repeat(TAB_WIDTH - (column % TAB_WIDTH)));
+    } else if (ch >= " " && ch !== "\x7f") {
+      let end = i + 1;
+      while (end < ansi.length && ansi[end] >= " " && ansi[end] !== "\x7f" && ansi[end] !== "\x1b") {
+        end++;
+      }
+      emit(ansi.slice(i, end));
+      i = end;
+      continue;
+    }
+    i++;
+  }
+
+  const rows = size.rows !== undefined ? lines.slice(0, size.rows) : lines;
+  return rows.map(trimStyledLine);
+}
+
+export interface CapturePosterOptions {
+  /** CLI argv (without argv[0]) */
+  argv?: string[];
+  env?: Record<string, string>;
+  cols?: number;
+  rows?: number;
+  /** How long to let the app run before snapshotting, with JSPI. Default: 500 */
+  durationMs?: number;
+  /** Data written to the app's stdin before it starts */
+  stdin?: string;
+  determinism?: TuiDeterminismOptions;
+  theme?: Partial<GhosttyTheme>;
+  /** Translate "\n" to "\r\n" like the live surface. Default: true */
+  convertEol?: boolean;
+  /** libghostty wasm: URL, file path (Node) or the bytes */
+  ghosttyWasm?: string | URL | BufferSource;
+}
+
+/**
+ * Run a guest headlessly for `durationMs` and snapshot its screen as a
+ * `poster` for `TuiPreview`. Meant for build scripts. Without JSPI
+ * (`WebAssembly.Suspending`) the guest runs synchronously, so stdin is closed
+ * after `stdin` and the poster is the screen it drew before reading EOF.
+ */
+export async function capturePoster(
+  wasm: string | URL,
+  options: CapturePosterOptions = {}
+): Promise<TuiPosterSnapshot> {
+  const cols = options.cols ?? 80;
+  const rows = options.rows ?? 24;
+  const terminal = await createHeadlessTerminal({
+    cols,
+    rows,
+    theme: options.theme,
+    convertEol: options.convertEol,
+    wasm: options.ghosttyWasm,
+  });
+
+  try {
+    let bridge: WasiBridge;
+    const writeOutput = (data: Uint8Array) => {
+      // What a guest prints on its way out after EOF isn't part of its screen.
+      if (bridge.hasReadEof) return;
+      terminal.write(data);
+      for (const response of terminal.drainResponses()) {
+        bridge.pushResponse(response);
+      }
+    };
+    bridge = new WasiBridge({
+      args: [wasm.toString(), ...(options.argv ?? [])],
+      env: { COLUMNS: String(cols), LINES: String(rows), ...options.env },
+      stdout: writeOutput,
+      stderr: writeOutput,
+      onExit: () => {},
+      determinism: options.determinism,
+    });
+    if (options.stdin) {
+      bridge.pushInput(options.stdin);
+    }
+    if (!hasJSPI) {
+      // Otherwise run() would never return: the guest would poll an empty stdin forever.
+      bridge.closeInput();
+    }
+
+    const app = await instantiateApp(wasm, bridge);
+    const running = app.run();
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    await Promise.race([
+      running,
+      new Promise<void>((resolve) => {
+        timer = setTimeout(resolve, options.durationMs ?? 500);
+      }),
+    ]);
+    clearTimeout(timer);
+    bridge.kill();
+    await running.catch(() => {});
+
+    const viewport = terminal.getViewportData();
+    return { cols: viewport.cols, rows: viewport.rows, cells: encodeCellBuffer(viewport.buffer) };
+  } finally {
+    terminal.dispose();
+  }
+}
@@ -0,0 +1,182 @@
+/**
+ * Worker entry for `createWorkerTerminalSurface`: owns the libghostty
+ * terminal and the renderer, painting into a transferred OffscreenCanvas.
+ */
+
+import type { GhosttyTheme } from "../types.js";
+import type { TerminalColorOptions } from "./contrast.js";
+import {
+  MiniRenderer,
+  loadLibGhostty,
+  loadTerminalFont,
+  resolveTheme,
+  whenFontLoaded,
+  type LibGhosttyTerminal,
+  type TerminalFontOptions,
+  type TerminalState,
+  type ViewportData,
+} from "./libghostty.js";
+
+export type RenderWorkerRequest =
+  | {
+      type: "init";
+      canvas: OffscreenCanvas;
+      cols: number;
+      rows: number;
+      fontSize: number;
+      fontFamily: string;
+      font: TerminalFontOptions;
+      colors: TerminalColorOptions;
+      theme?: Partial<GhosttyTheme>;
+      /** Absolute URL; the worker resolves relative URLs against its own script. */
+      wasmUrl?: string;
+      devicePixelRatio: number;
+      showCursor: boolean;
+      fontLoadTimeout?: number;
+    }
+  | { type: "write"; data: string }
+  | { type: "resize"; cols: number; rows: number }
+  | { type: "suspend"; suspended: boolean }
+  | { type: "devicePixelRatio"; devicePixelRatio: number }
+  | { type: "png"; id: number };
+
+export type RenderWorkerEvent =
+  | { type: "ready"; cellSize: { w: number; h: number } }
+  /** Cell size after re-measuring the font (a new devicePixelRatio, a late web font). */
+  | { type: "cellSize"; cellSize: { w: number; h: number } }
+  | { type: "error"; message: string }
+  /** Screen after each frame, so the page can answer getText() and friends without a round trip. */
+  | { type: "frame"; viewport: ViewportData; state: TerminalState | null }
+  | { type: "response"; data: string }
+  | { type: "title"; title: string }
+  | { type: "png"; id: number; blob: Blob | null; message?: string };
+
+interface WorkerScope {
+  onmessage: ((event: MessageEvent<RenderWorkerRequest>) => void) | null;
+  postMessage(message: RenderWorkerEvent, transfer?: Transferable[]): void;
+  requestAnimationFrame?: (callback: FrameRequestCallback) => number;
+}
+
+const scope = self as unknown as WorkerScope;
+const post = (message: RenderWorkerEvent, transfer: Transferable[] = []) =>
+  scope.postMessage(message, transfer);
+const requestFrame: (callback: () => void) => unknown =
+  scope.requestAnimationFrame?.bind(scope) ?? ((callback) => setTimeout(callback, 16));
+
+let canvas: OffscreenCanvas | null = null;
+let terminal: LibGhosttyTerminal | null = null;
+let renderer: MiniRenderer | null = null;
+let framePending = false;
+let suspended = false;
+// Rows taken while suspended were never painted; repaint everything on resume.
+let repaintAll = false;
+
+function renderFrame() {
+  framePending = false;
+  if (!terminal || !renderer) return;
+  const dirty = terminal.isDirty();
+  if (!dirty && (suspended || !repaintAll)) return;
+  const { viewport, rows } = terminal.takeDirtyRows();
+  if (suspended) {
+    repaintAll = true;
+  } else {
+    renderer.render(viewport, repaintAll ? undefined : rows);
+    repaintAll = false;
+  }
+  if (!dirty) return;
+  const buffer = viewport.buffer.slice();
+  post(
+    { type: "frame", viewport: { cols: viewport.cols, rows: viewport.rows, buffer }, state: terminal.getState() },
+    [buffer.buffer as ArrayBuffer]
+  );
+}
+
+function scheduleRender() {
+  if (framePending) return;
+  framePending = true;
+  requestFrame(renderFrame);
+}
+
+async function handle(request: RenderWorkerRequest) {
+  switch (request.type) {
+    case "init": {
+      // Only fonts the worker itself can load count here; document @font-face rules don't apply.
+      const [runtime, fontLoaded] = await Promise.all([
+        loadLibGhostty(request.wasmUrl),
+        loadTerminalFont(request.fontSize, request.fontFamily, request.fontLoadTimeout, request.font),
+      ]);
+      const theme = resolveTheme(request.theme);
+      canvas = request.canvas;
+      terminal = runtime.createTerminal(request.cols, request.rows, theme);
+      renderer = new MiniRenderer(
+        canvas,
+        request.cols,
+        request.rows,
+        request.fontSize,
+        request.fontFamily,
+        theme,
+        { ...request.font, ...request.colors, devicePixelRatio: request.devicePixelRatio }
+      );
+      if (!request.showCursor) {
+        terminal.write("\x1b[?25l");
+      }
+      renderer.render(terminal.takeDirtyRows().viewport);
+      post({ type: "ready", cellSize: renderer.cellSize });
+      if (!fontLoaded) {
+        void whenFontLoaded(request.fontSize, request.fontFamily, request.font).then(() => {
+          if (!renderer) return;
+          renderer.remeasureFont();
+          post({ type: "cellSize", cellSize: renderer.cellSize });
+          repaintAll = true;
+          scheduleRender();
+        });
+      }
+      return;
+    }
+    case "write": {
+      if (!terminal) return;
+      terminal.write(request.data);
+      for (const data of terminal.drainResponses()) {
+        post({ type: "response", data });
+      }
+      const title = terminal.takeTitle();
+      if (title !== null) post({ type: "title", title });
+      scheduleRender();
+      return;
+    }
+    case "resize":
+      terminal?.resize(request.cols, request.rows);
+      scheduleRender();
+      return;
+    case "suspend":
+      suspended = request.suspended;
+      if (!suspended && repaintAll) scheduleRender();
+      return;
+    case "devicePixelRatio": {
+      if (!renderer) return;
+      renderer.setDevicePixelRatio(request.devicePixelRatio);
+      post({ type: "cellSize", cellSize: renderer.cellSize });
+      repaintAll = true;
+      scheduleRender();
+      return;
+    }
+    case "png": {
+      if (!canvas) return;
+      if (!suspended) renderFrame();
+      try {
+        post({ type: "png", id: request.id, blob: await canvas.convertToBlob({ type: "image/png" }) });
+      } catch (error) {
+        post({ type: "png", id: request.id, blob: null, message: String(error) });
+      }
+      return;
+    }
+  }
+}
+
+// Requests are handled strictly in order; writes sent during init wait for it.
+let queue = Promise.resolve();
+scope.onmessage = (event) => {
+  queue = queue
+    .then(() => handle(event.data))
+    .catch((error) => post({ type: "error", message: error instanceof Error ? error.message : String(error) }));
+};
@@ -0,0 +1,250 @@
+/**
+ * Scripted input playback — types, presses keys and waits for screen text so
+ * a preview can demo itself.
+ */
+
+import type { TuiScriptStep } from "../types.js";
+import { encodeKey } from "./libghostty.js";
+
+const DEFAULT_TYPING_DELAY_MS = 50;
+const DEFAULT_WAIT_FOR_TEXT_TIMEOUT_MS = 10_000;
+
+export type ScriptPlayerState = "idle" | "playing" | "paused" | "stopped" | "done";
+
+export type NormalizedScriptStep =
+  | { kind: "type"; text: string; delay: number }
+  | { kind: "press"; keys: string[] }
+  | { kind: "wait"; ms: number }
+  | { kind: "waitForText"; match: string | RegExp; timeout: number };
+
+/** A countdown that stops while the player is paused and picks up where it left off. */
+interface PlaybackTimer {
+  callback: () => void;
+  remaining: number;
+  startedAt: number;
+  handle: ReturnType<typeof setTimeout> | null;
+}
+
+export interface ScriptPlayerTarget {
+  sendInput(data: string): void;
+  /** Plain text of the visible screen */
+  getText(): string;
+}
+
+function parseDuration(value: string, unit: string | undefined): number {
+  const amount = Number.parseFloat(value);
+  return unit === "s" ? amount * 1000 : amount;
+}
+
+/**
+ * Parse one textual step: `type "hello"`, `press Down Enter`, `wait 500ms`,
+ * `waitForText "Game Over" 5s`. Quoted strings use JSON escapes.
+ */
+export function parseScriptStep(line: string, typingDelay = DEFAULT_TYPING_DELAY_MS): NormalizedScriptStep {
+  const source = line.trim();
+  let match = source.match(/^type\s+(".*")$/);
+  if (match) {
+    return { kind: "type", text: JSON.parse(match[1]) as string, delay: typingDelay };
+  }
+  match = source.match(/^press\s+(.+)$/);
+  if (match) {
+    return { kind: "press", keys: match[1].split(/\s+/) };
+  }
+  match = source.match(/^wait\s+(\d+(?:\.\d+)?)(ms|s)?$/);
+  if (match) {
+    return { kind: "wait", ms: parseDuration(match[1], match[2]) };
+  }
+  match = source.match(/^waitForText\s+(".*")(?:\s+(\d+(?:\.\d+)?)(ms|s)?)?$/);
+  if (match) {
+    return {
+      kind: "waitForText",
+      match: JSON.parse(match[1]) as string,
+      timeout: match[2] ? parseDuration(match[2], match[3]) : DEFAULT_WAIT_FOR_TEXT_TIMEOUT_MS,
+    };
+  }
+  throw new Error(`Invalid script step: ${line}`);
+}
+
+export function normalizeScriptStep(
+  step: TuiScriptStep,
+  typingDelay = DEFAULT_TYPING_DELAY_MS
+): NormalizedScriptStep {
+  if (typeof step === "string") {
+    return parseScriptStep(step, typingDelay);
+  }
+  if ("type" in step) {
+    return { kind: "type", text: step.type, delay: step.delay ?? typingDelay };
+  }
+  if ("press" in step) {
+    return { kind: "press", keys: Array.isArray(step.press) ? step.press : [step.press] };
+  }
+  if ("wait" in step) {
+    return { kind: "wait", ms: step.wait };
+  }
+  return {
+    kind: "waitForText",
+    match: step.waitForText,
+    timeout: step.timeout ?? DEFAULT_WAIT_FOR_TEXT_TIMEOUT_MS,
+  };
+}
+
+/** Plays normalized steps against a target, with pause/resume/stop. */
+export class ScriptPlayer {
+  private current: ScriptPlayerState = "idle";
+  private resumeWaiters: Array<() => void> = [];
+  private outputWaiters: Array<() => void> = [];
+  /** Pending sleeps and waitForText deadlines; stop() fires them to release their awaiters. */
+  private timers = new Set<PlaybackTimer>();
+
+  constructor(
+    private readonly steps: NormalizedScriptStep[],
+    private readonly target: ScriptPlayerTarget
+  ) {}
+
+  get state(): ScriptPlayerState {
+    return this.current;
+  }
+
+  /** Run every step in order. Resolves with "done", or "stopped" if stop() was called. */
+  async play(): Promise<"done" | "stopped"> {
+    if (this.current !== "idle") {
+      throw new Error(`Script player already ${this.current}.`);
+    }
+    this.current = "playing";
+    try {
+      for (const step of this.steps) {
+        if (!(await this.checkpoint())) return "stopped";
+        await this.runStep(step);
+      }
+    } catch (e) {
+      this.stop();
+      throw e;
+    }
+    if (!(await this.checkpoint())) return "stopped";
+    this.current = "done";
+    return "done";
+  }
+
+  pause() {
+    if (this.current !== "playing") return;
+    this.current = "paused";
+    const now = Date.now();
+    for (const timer of this.timers) {
+      if (timer.handle === null) continue;
+      clearTimeout(timer.handle);
+      timer.handle = null;
+      timer.remaining = Math.max(0, timer.remaining - (now - timer.startedAt));
+    }
+  }
+
+  resume() {
+    if (this.current !== "paused") return;
+    this.current = "playing";
+    for (const timer of this.timers) this.armTimer(timer);
+    for (const resolve of this.resumeWaiters.splice(0)) resolve();
+  }
+
+  stop() {
+    if (this.current === "done" || this.current === "stopped") return;
+    this.current = "stopped";
+    const timers = [...this.timers];
+    this.timers.clear();
+    for (const timer of timers) {
+      if (timer.handle !== null) clearTimeout(timer.handle);
+      timer.callback();
+    }
+    for (const resolve of this.resumeWaiters.splice(0)) resolve();
+    for (const resolve of this.outputWaiters.splice(0)) resolve();
+  }
+
+  /** Call whenever the screen may have changed, to re-check pending waitForText steps. */
+  notifyOutput() {
+    for (const resolve of this.outputWaiters.splice(0)) resolve();
+  }
+
+  private async runStep(step: NormalizedScriptStep) {
+    switch (step.kind) {
+      case "type":
+        for (const char of step.text) {
+          if (!(await this.checkpoint())) return;
+          this.target.sendInput(char);
+          if (step.delay > 0) await this.sleep(step.delay);
+        }
+        return;
+      case "press":
+        for (const key of step.keys) {
+          const encoded = encodeKey(key);
+          if (encoded === null) {
+            throw new Error(`Unknown key: ${key}`);
+          }
+          if (!(await this.checkpoint())) return;
+          this.target.sendInput(encoded);
+        }
+        return;
+      case "wait":
+        await this.sleep(step.ms);
+        return;
+      case "waitForText":
+        await this.waitForText(step.match, step.timeout);
+        return;
+    }
+  }
+
+  /** Block while paused. Returns false once the player has been stopped. */
+  private async checkpoint(): Promise<boolean> {
+    while (this.current === "paused") {
+      await new Promise<void>((resolve) => this.resumeWaiters.push(resolve));
+    }
+    return this.current === "playing";
+  }
+
+  /** Call `callback` after `ms` of playing time; the countdown doesn't run while paused. */
+  private startTimer(ms: number, callback: () => void): PlaybackTimer {
+    const timer: PlaybackTimer = { callback, remaining: ms, startedAt: 0, handle: null };
+    this.timers.add(timer);
+    if (this.current === "playing") this.armTimer(timer);
+    return timer;
+  }
+
+  private armTimer(timer: PlaybackTimer) {
+    timer.startedAt = Date.now();
+    timer.handle = setTimeout(() => {
+      this.timers.delete(timer);
+      timer.callback();
+    }, timer.remaining);
+  }
+
+  private cancelTimer(timer: PlaybackTimer) {
+    if (timer.handle !== null) clearTimeout(timer.handle);
+    this.timers.delete(timer);
+  }
+
+  private sleep(ms: number): Promise<void> {
+    if (this.current === "stopped") return Promise.resolve();
+    return new Promise<void>((resolve) => this.startTimer(ms, resolve));
+  }
+
+  private async waitForText(match: string | RegExp, timeoutMs: number) {
+    let timedOut = false;
+    const deadline = this.startTimer(timeoutMs, () => {
+      timedOut = true;
+      this.notifyOutput();
+    });
+    const matches = () => {
+      const text = this.target.getText();
+      if (typeof match === "string") return text.includes(match);
+      match.lastIndex = 0;
+      return match.test(text);
+    };
+    try {
+      while (this.current !== "stopped" && !matches()) {
+        if (timedOut) {
+          throw new Error(`Timed out waiting for text: ${String(match)}`);
+        }
+        await new Promise<void>((resolve) => this.outputWaiters.push(resolve));
+      }
+    } finally {
+      this.cancelTimer(deadline);
+    }
+  }
+}
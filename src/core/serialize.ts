@@ -0,0 +1,206 @@
+/**
+ * ANSI serialization — re-encode a screen as a minimal byte stream that
+ * reproduces it when written into a fresh terminal of the same size.
+ */
+
+import {
+  EXPECTED_CELL_SIZE,
+  FLAG_BG_EXPLICIT,
+  FLAG_BOLD,
+  FLAG_FAINT,
+  FLAG_FG_EXPLICIT,
+  FLAG_INVERSE,
+  FLAG_INVISIBLE,
+  FLAG_ITALIC,
+  FLAG_UNDERLINE,
+  cellColumns,
+  type RgbColor,
+  type TerminalState,
+  type ViewportData,
+} from "./libghostty.js";
+
+const ESC = "\x1b";
+
+/** SGR parameter per attribute flag, in emission order. */
+const ATTRIBUTE_SGR: [flag: number, param: number][] = [
+  [FLAG_BOLD, 1],
+  [FLAG_FAINT, 2],
+  [FLAG_ITALIC, 3],
+  [FLAG_UNDERLINE, 4],
+  [FLAG_INVERSE, 7],
+  [FLAG_INVISIBLE, 8],
+];
+const ATTRIBUTE_MASK = ATTRIBUTE_SGR.reduce((mask, [flag]) => mask | flag, 0);
+
+interface Pen {
+  flags: number;
+  fg: RgbColor | null;
+  bg: RgbColor | null;
+}
+
+const DEFAULT_PEN: Pen = { flags: 0, fg: null, bg: null };
+
+function sameColor(a: RgbColor | null, b: RgbColor | null): boolean {
+  if (a === null || b === null) return a === b;
+  return a.r === b.r && a.g === b.g && a.b === b.b;
+}
+
+function samePen(a: Pen, b: Pen): boolean {
+  return a.flags === b.flags && sameColor(a.fg, b.fg) && sameColor(a.bg, b.bg);
+}
+
+/** Shortest SGR taking the terminal from `from` to `to`; resets when an attribute turns off. */
+function sgrDiff(from: Pen, to: Pen): string {
+  if (samePen(from, to)) return "";
+  const params: string[] = [];
+  let base = from;
+  if (from.flags & ~to.flags) {
+    params.push("0");
+    base = DEFAULT_PEN;
+  }
+  for (const [flag, param] of ATTRIBUTE_SGR) {
+    if (to.flags & flag && !(base.flags & flag)) params.push(String(param));
+  }
+  if (!sameColor(base.fg, to.fg)) {
+    params.push(to.fg ? `38;2;${to.fg.r};${to.fg.g};${to.fg.b}` : "39");
+  }
+  if (!sameColor(base.bg, to.bg)) {
+    params.push(to.bg ? `48;2;${to.bg.r};${to.bg.g};${to.bg.b}` : "49");
+  }
+  return params.length > 0 ? `${ESC}[${params.join(";")}m` : "";
+}
+
+function readRgb(view: DataView, offset: number): RgbColor {
+  return { r: view.getUint8(offset), g: view.getUint8(offset + 1), b: view.getUint8(offset + 2) };
+}
+
+function cellPen(view: DataView, base: number): Pen {
+  const flags = view.getUint8(base + 10);
+  return {
+    flags: flags & ATTRIBUTE_MASK,
+    fg: flags & FLAG_FG_EXPLICIT ? readRgb(view, base + 4) : null,
+    bg: flags & FLAG_BG_EXPLICIT ? readRgb(view, base + 7) : null,
+  };
+}
+
+function cellText(codepoint: number): string {
+  try {
+    return String.fromCodePoint(codepoint);
+  } catch {
+    return " ";
+  }
+}
+
+/**
+ * Re-encode a viewport (and, when available, cursor/margins/modes from
+ * `LibGhosttyTerminal.getState`) as ANSI. Writing the result into a fresh
+ * terminal of the same size reproduces the cells, cursor, saved cursor,
+ * scroll margins, wraparound/origin, cursor visibility, alternate screen,
+ * application cursor keys and bracketed paste modes, and the current SGR
+ * pen. Without `state` the cursor ends at the home position.
+ *
+ * Scrollback is not serialized: the bundled wrapper keeps none, so only
+ * the visible screen can be saved. On the alternate screen the cells are
+ * drawn after `?1049h`, leaving the primary screen blank on replay.
+ */
+export function serializeScreen(viewport: ViewportData, state: TerminalState | null = null): string {
+  const { cols, rows, buffer } = viewport;
+  const view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  const cellBase = (x: number, y: number) => (y * cols + x) * EXPECTED_CELL_SIZE;
+  let out = `${ESC}c`;
+  if (state?.altScreen) out += `${ESC}[?1049h`;
+  let pen = DEFAULT_PEN;
+  const setPen = (next: Pen) => {
+    out += sgrDiff(pen, next);
+    pen = next;
+  };
+
+  for (let y = 0; y < rows; y++) {
+    // Column the terminal cursor is at on this row, or -1 before the first CUP.
+    let cursor = -1;
+    const moveTo = (x: number) => {
+      if (cursor === x) return;
+      out += cursor === -1 ? `${ESC}[${y + 1};${x + 1}H` : `${ESC}[${x + 1}G`;
+      cursor = x;
+    };
+    let x = 0;
+    while (x < cols) {
+      const base = cellBase(x, y);
+      if (base + EXPECTED_CELL_SIZE > buffer.byteLength) break;
+      const codepoint = view.getUint32(base, true);
+      const width = cellColumns(view, base, x);
+      const flags = view.getUint8(base + 10);
+      if (codepoint !== 0 && width > 0) {
+        moveTo(x);
+        setPen(cellPen(view, base));
+        out += cellText(codepoint);
+        cursor = x + width;
+        x += width;
+        continue;
+      }
+      if (codepoint === 0 && width > 0 && flags & FLAG_BG_EXPLICIT) {
+        // Erased cells keep only their background: re-create the run with ECH.
+        const bg = readRgb(view, base + 7);
+        let run = 1;
+        while (x + run < cols) {
+          const next = cellBase(x + run, y);
+          if (
+            view.getUint32(next, true) !== 0 ||
+            !(view.getUint8(next + 10) & FLAG_BG_EXPLICIT) ||
+            !sameColor(readRgb(view, next + 7), bg)
+          ) {
+            break;
+          }
+          run++;
+        }
+        moveTo(x);
+        setPen({ flags: 0, fg: null, bg });
+        out += run > 1 ? `${ESC}[${run}X` : `${ESC}[X`;
+        x += run;
+        continue;
+      }
+      x++;
+    }
+  }
+
+  setPen(DEFAULT_PEN);
+  if (!state) {
+    return out + `${ESC}[H`;
+  }
+
+  if (state.savedX !== 0 || state.savedY !== 0) {
+    out += `${ESC}[${state.savedY + 1};${state.savedX + 1}H${ESC}7`;
+  }
+  if (state.leftRightMargin) {
+    out += `${ESC}[?69h`;
+    if (state.scrollLeft !== 0 || state.scrollRight !== cols - 1) {
+      out += `${ESC}[${state.scrollLeft + 1};${state.scrollRight + 1}s`;
+    }
+  }
+  if (state.scrollTop !== 0 || state.scrollBottom !== rows - 1) {
+    out += `${ESC}[${state.scrollTop + 1};${state.scrollBottom + 1}r`;
+  }
+  if (!state.wraparound) out += `${ESC}[?7l`;
+  if (state.originMode) out += `${ESC}[?6h`;
+  if (state.applicationCursorKeys) out += `${ESC}[?1h`;
+  if (state.bracketedPaste) out += `${ESC}[?2004h`;
+  if (!state.cursorVisible) out += `${ESC}[?25l`;
+
+  // CUP is relative to the margins in origin mode.
+  const rowOffset = state.originMode ? state.scrollTop : 0;
+  const colOffset = state.originMode && state.leftRightMargin ? state.scrollLeft : 0;
+  out += `${ESC}[${state.cursorY - rowOffset + 1};${state.cursorX - colOffset + 1}H`;
+
+  // A pending wrap is re-armed by reprinting the character under the cursor.
+  const cursorBase = cellBase(state.cursorX, state.cursorY);
+  if (state.pendingWrap && cursorBase + EXPECTED_CELL_SIZE <= buffer.byteLength) {
+    const codepoint = view.getUint32(cursorBase, true);
+    if (codepoint !== 0 && cellColumns(view, cursorBase, state.cursorX) > 0) {
+      setPen(cellPen(view, cursorBase));
+      out += cellText(codepoint);
+    }
+  }
+
+  setPen({ flags: state.styleFlags & ATTRIBUTE_MASK, fg: state.fg, bg: state.bg });
+  return out;
+}
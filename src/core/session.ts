@@ -0,0 +1,125 @@
+/**
+ * Saved sessions — a guest's linear memory and globals, its WASI state and
+ * its screen, captured while it waits for input and restored into a fresh
+ * instance later (e.g. after the reader navigates away and back).
+ */
+
+import type { TuiRuntimeSize, TuiSessionSnapshot } from "../types.js";
+import { decodeBase64, encodeBase64 } from "./base64.js";
+import type { TerminalState, ViewportData } from "./libghostty.js";
+import { serializeScreen } from "./serialize.js";
+import { instantiateApp, type WasiApp, type WasiBridge } from "./wasi.js";
+
+/** The terminal side of a session: whatever holds the screen the guest drew. */
+export interface SessionTerminal {
+  readonly cols: number;
+  readonly rows: number;
+  write(data: string): void;
+  resize(cols: number, rows: number): void;
+  getViewportData(): ViewportData;
+  getState(): TerminalState | null;
+}
+
+/**
+ * Snapshot a running app, or null if it can't be resumed (no
+ * `tui_preview_resume` export). Take it while the guest is suspended waiting
+ * for input; under JSPI that is whenever host code runs.
+ */
+export function captureSession(
+  wasm: string | URL,
+  app: WasiApp,
+  bridge: WasiBridge,
+  terminal: SessionTerminal
+): TuiSessionSnapshot | null {
+  if (!app.resumable) return null;
+  return {
+    version: 1,
+    wasm: wasm.toString(),
+    cols: terminal.cols,
+    rows: terminal.rows,
+    guest: app.snapshot(),
+    bridge: bridge.saveState(),
+    terminal: serializeScreen(terminal.getViewportData(), terminal.getState()),
+  };
+}
+
+/**
+ * Redraw a saved screen into `terminal`, load the WASI state into `bridge`
+ * and instantiate `wasm` with the saved memory. `run()` on the result enters
+ * the guest through its resume export. `size` is the grid to redraw at,
+ * e.g. a container fit that changed since the save; default: the saved one.
+ */
+export async function restoreSession(
+  session: TuiSessionSnapshot,
+  wasm: string | URL,
+  bridge: WasiBridge,
+  terminal: SessionTerminal,
+  size: TuiRuntimeSize = session
+): Promise<WasiApp> {
+  if (session.version !== 1) {
+    throw new Error(`Unsupported session version: ${String(session.version)}`);
+  }
+  if (session.wasm !== wasm.toString()) {
+    throw new Error(`Saved session belongs to ${session.wasm}, not ${wasm.toString()}.`);
+  }
+  const app = await instantiateApp(wasm, bridge, { restore: session.guest });
+  bridge.restoreState(session.bridge);
+  terminal.resize(size.cols, size.rows);
+  terminal.write(session.terminal);
+  return app;
+}
+
+/** JSON for storage; bigints become decimal strings with an `n` suffix. */
+export function encodeSession(session: TuiSessionSnapshot): string {
+  return JSON.stringify(
+    {
+      ...session,
+      guest: { ...session.guest, memory: encodeBase64(session.guest.memory) },
+      bridge: {
+        ...session.bridge,
+        input: session.bridge.input.map((chunk) => ({ ...chunk, data: encodeBase64(chunk.data) })),
+      },
+    },
+    (_key, value) => (typeof value === "bigint" ? `${value}n` : value)
+  );
+}
+
+function reviveNumber(value: unknown): number | bigint {
+  if (typeof value === "string" && /^-?\d+n$/.test(value)) {
+    return BigInt(value.slice(0, -1));
+  }
+  return Number(value);
+}
+
+export function decodeSession(text: string): TuiSessionSnapshot {
+  const raw = JSON.parse(text);
+  if (raw?.version !== 1 || typeof raw.wasm !== "string" || !raw.guest || !raw.bridge) {
+    throw new Error("Not a saved tui-preview session.");
+  }
+  const globals: Record<string, number | bigint> = {};
+  for (const [name, value] of Object.entries(raw.guest.globals ?? {})) {
+    globals[name] = reviveNumber(value);
+  }
+  return {
+    version: 1,
+    wasm: raw.wasm,
+    cols: Number(raw.cols),
+    rows: Number(raw.rows),
+    guest: {
+      memorySize: Number(raw.guest.memorySize),
+      memory: decodeBase64(raw.guest.memory),
+      globals,
+    },
+    bridge: {
+      input: (raw.bridge.input ?? []).map((chunk: { data: string; response: unknown }) => ({
+        data: decodeBase64(chunk.data),
+        response: Boolean(chunk.response),
+      })),
+      inputClosed: Boolean(raw.bridge.inputClosed),
+      virtualTimeNs:
+        raw.bridge.virtualTimeNs === null ? null : BigInt(reviveNumber(raw.bridge.virtualTimeNs)),
+      randomState: raw.bridge.randomState === null ? null : Number(raw.bridge.randomState),
+    },
+    terminal: String(raw.terminal),
+  };
+}
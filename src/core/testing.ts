@@ -0,0 +1,243 @@
+/**
+ * Snapshot testing harness — run a wasm32-wasi guest headlessly, drive it
+ * with input steps and capture the settled screen as text and styles.
+ */
+
+import type {
+  GhosttyTheme,
+  TuiDeterminismOptions,
+  TuiRuntimeSize,
+  TuiScriptStep,
+} from "../types.js";
+import { createHeadlessTerminal, type TerminalCell } from "./headless.js";
+import { encodeKeys } from "./libghostty.js";
+import { normalizeScriptStep, type NormalizedScriptStep } from "./script.js";
+import { WasiBridge, hasJSPI, instantiateApp } from "./wasi.js";
+
+const DEFAULT_IDLE_TIMEOUT_MS = 5_000;
+const WAIT_FOR_TEXT_POLL_MS = 10;
+
+export interface RunTuiOptions {
+  /** CLI argv (without argv[0]) */
+  argv?: string[];
+  env?: Record<string, string>;
+  /** Terminal size. Default: 80x24 */
+  size?: TuiRuntimeSize;
+  /** Input steps, in the same forms as the `script` prop; the screen settles after each one */
+  steps?: TuiScriptStep[];
+  /** Seeded randomness and virtual time. Default: true, so snapshots are reproducible */
+  determinism?: boolean | TuiDeterminismOptions;
+  /** Max time to wait for the guest to go idle, per step. Default: 5000 */
+  timeout?: number;
+  theme?: Partial<GhosttyTheme>;
+  /** libghostty wasm: URL, file path (Node) or the bytes */
+  ghosttyWasm?: string | URL | BufferSource;
+}
+
+export interface TuiSnapshot {
+  /** Plain screen text, trailing spaces trimmed */
+  text: string;
+  /** Non-default style runs, one per line: `row:start-end attr ...` */
+  styles: string;
+  cells: TerminalCell[][];
+  /** Exit code if the guest exited, otherwise null */
+  exitCode: number | null;
+}
+
+function styleKey(cell: TerminalCell): string {
+  const attrs: string[] = [];
+  if (cell.bold) attrs.push("bold");
+  if (cell.faint) attrs.push("faint");
+  if (cell.italic) attrs.push("italic");
+  if (cell.underline) attrs.push("underline");
+  if (cell.inverse) attrs.push("inverse");
+  if (cell.invisible) attrs.push("invisible");
+  if (cell.fg) attrs.push(`fg=${cell.fg}`);
+  if (cell.bg) attrs.push(`bg=${cell.bg}`);
+  return attrs.join(" ");
+}
+
+/** Describe styled runs compactly, e.g. `3:10-17 bold fg=#7dcfff`. Columns are end-exclusive. */
+export function describeStyles(cells: TerminalCell[][]): string {
+  const lines: string[] = [];
+  cells.forEach((row, y) => {
+    let start = 0;
+    let current = "";
+    const flush = (end: number) => {
+      if (current) lines.push(`${y}:${start}-${end} ${current}`);
+    };
+    row.forEach((cell, x) => {
+      const key = styleKey(cell);
+      if (key !== current) {
+        flush(x);
+        start = x;
+        current = key;
+      }
+    });
+    flush(row.length);
+  });
+  return lines.join("\n");
+}
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+/** stdin for a `type` or `press` step: one chunk per character or key, as they'd be typed. */
+function stepInput(step: NormalizedScriptStep): string[] | null {
+  switch (step.kind) {
+    case "type":
+      return [...step.text];
+    case "press":
+      return encodeKeys(step.keys);
+    default:
+      return null;
+  }
+}
+
+/**
+ * Run a guest, apply `steps`, and return the settled screen. The guest is
+ * killed afterwards.
+ *
+ * With JSPI (`WebAssembly.Suspending`) the screen settles after each step,
+ * once the guest blocks waiting for input. Without it the guest runs
+ * synchronously: every step's input is queued up front and stdin closed, and
+ * the snapshot is the screen drawn before the guest reads EOF. That only
+ * works for `type` and `press` steps, with a guest that returns at EOF.
+ */
+export async function runTui(wasm: string | URL, options: RunTuiOptions = {}): Promise<TuiSnapshot> {
+  const steps = (options.steps ?? []).map((step) => normalizeScriptStep(step, 0));
+  if (!hasJSPI) {
+    const blocking = steps.find((step) => stepInput(step) === null);
+    if (blocking) {
+      throw new Error(`runTui needs JSPI (WebAssembly.Suspending) for ${blocking.kind} steps.`);
+    }
+  }
+  const cols = options.size?.cols ?? 80;
+  const rows = options.size?.rows ?? 24;
+  const timeout = options.timeout ?? DEFAULT_IDLE_TIMEOUT_MS;
+  const determinism =
+    options.determinism === false
+      ? undefined
+      : options.determinism === true || options.determinism === undefined
+        ? { seed: 1, epochMs: 0 }
+        : options.determinism;
+
+  const terminal = await createHeadlessTerminal({
+    cols,
+    rows,
+    theme: options.theme,
+    wasm: options.ghosttyWasm,
+  });
+  let exitCode: number | null = null;
+  let bridge: WasiBridge;
+  const writeOutput = (data: Uint8Array) => {
+    // What a guest prints on its way out after EOF isn't part of its screen.
+    if (bridge.hasReadEof) return;
+    terminal.write(data);
+    for (const response of terminal.drainResponses()) {
+      bridge.pushResponse(response);
+    }
+  };
+  bridge = new WasiBridge({
+    args: [wasm.toString(), ...(options.argv ?? [])],
+    env: { COLUMNS: String(cols), LINES: String(rows), ...options.env },
+    stdout: writeOutput,
+    stderr: writeOutput,
+    onExit: (code) => {
+      // Running out of queued input isn't an exit the guest chose.
+      if (!bridge.hasReadEof) exitCode ??= code;
+    },
+    determinism,
+  });
+
+  const capture = (): TuiSnapshot => {
+    const cells = terminal.getCells();
+    return {
+      text: terminal.getText(),
+      styles: describeStyles(cells),
+      cells,
+      exitCode,
+    };
+  };
+
+  try {
+    const app = await instantiateApp(wasm, bridge);
+    if (!hasJSPI) {
+      for (const step of steps) {
+        for (const chunk of stepInput(step) ?? []) bridge.pushInput(chunk);
+      }
+      bridge.closeInput();
+      await app.run();
+      return capture();
+    }
+
+    let finished = false;
+    const running = app.run().finally(() => {
+      finished = true;
+    });
+
+    const settle = async (label: string) => {
+      let timer: ReturnType<typeof setTimeout> | undefined;
+      try {
+        await Promise.race([
+          bridge.whenIdle(),
+          running,
+          new Promise<never>((_, reject) => {
+            timer = setTimeout(
+              () => reject(new Error(`Timed out waiting for the guest to go idle after ${label}.`)),
+              timeout
+            );
+          }),
+        ]);
+      } finally {
+        clearTimeout(timer);
+      }
+    };
+
+    const runStep = async (step: NormalizedScriptStep) => {
+      switch (step.kind) {
+        case "type":
+          for (const chunk of stepInput(step) ?? []) bridge.pushInput(chunk);
+          return settle(`type ${JSON.stringify(step.text)}`);
+        case "press":
+          for (const chunk of stepInput(step) ?? []) bridge.pushInput(chunk);
+          return settle(`press ${step.keys.join(" ")}`);
+        case "wait":
+          await sleep(step.ms);
+          return settle(`wait ${step.ms}ms`);
+        case "waitForText": {
+          const deadline = Date.now() + step.timeout;
+          const matches = () => {
+            const text = terminal.getText();
+            if (typeof step.match === "string") return text.includes(step.match);
+            step.match.lastIndex = 0;
+            return step.match.test(text);
+          };
+          while (!matches()) {
+            if (finished || Date.now() > deadline) {
+              throw new Error(`Timed out waiting for text: ${String(step.match)}`);
+            }
+            await sleep(WAIT_FOR_TEXT_POLL_MS);
+            await settle(`waitForText ${String(step.match)}`);
+          }
+          return;
+        }
+      }
+    };
+
+    await settle("startup");
+    for (const step of steps) {
+      if (finished) break;
+      await runStep(step);
+    }
+
+    const snapshot = capture();
+    bridge.kill();
+    await running.catch(() => {});
+    return snapshot;
+  } finally {
+    bridge.kill();
+    terminal.dispose();
+  }
+}
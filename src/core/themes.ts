@@ -0,0 +1,271 @@
+/**
+ * Theme presets, and parsers that turn Ghostty theme files, iTerm2
+ * `.itermcolors`, Windows Terminal color schemes and base16 YAML into a
+ * `GhosttyTheme`.
+ */
+
+import type { GhosttyTheme } from "../types.js";
+import { ANSI_THEME_KEYS, resolveTheme } from "./libghostty.js";
+
+/** Palettes in ANSI order: black, red, green, yellow, blue, magenta, cyan, white, then the bright ones. */
+function preset(
+  colors: Pick<GhosttyTheme, "background" | "foreground" | "cursor" | "selectionBackground" | "selectionForeground">,
+  palette: readonly string[]
+): GhosttyTheme {
+  const theme = { ...colors } as GhosttyTheme;
+  ANSI_THEME_KEYS.forEach((key, index) => {
+    theme[key] = palette[index];
+  });
+  return theme;
+}
+
+const SOLARIZED_PALETTE = [
+  "#073642", "#dc322f", "#859900", "#b58900", "#268bd2", "#d33682", "#2aa198", "#eee8d5",
+  "#002b36", "#cb4b16", "#586e75", "#657b83", "#839496", "#6c71c4", "#93a1a1", "#fdf6e3",
+];
+
+export const THEME_PRESETS = {
+  tokyoNight: resolveTheme(),
+  dracula: preset(
+    {
+      background: "#282a36",
+      foreground: "#f8f8f2",
+      cursor: "#f8f8f2",
+      selectionBackground: "#44475a",
+      selectionForeground: "#f8f8f2",
+    },
+    [
+      "#21222c", "#ff5555", "#50fa7b", "#f1fa8c", "#bd93f9", "#ff79c6", "#8be9fd", "#f8f8f2",
+      "#6272a4", "#ff6e6e", "#69ff94", "#ffffa5", "#d6acff", "#ff92df", "#a4ffff", "#ffffff",
+    ]
+  ),
+  solarizedDark: preset(
+    {
+      background: "#002b36",
+      foreground: "#839496",
+      cursor: "#93a1a1",
+      selectionBackground: "#073642",
+      selectionForeground: "#93a1a1",
+    },
+    SOLARIZED_PALETTE
+  ),
+  solarizedLight: preset(
+    {
+      background: "#fdf6e3",
+      foreground: "#657b83",
+      cursor: "#586e75",
+      selectionBackground: "#eee8d5",
+      selectionForeground: "#586e75",
+    },
+    SOLARIZED_PALETTE
+  ),
+  gruvboxDark: preset(
+    {
+      background: "#282828",
+      foreground: "#ebdbb2",
+      cursor: "#ebdbb2",
+      selectionBackground: "#504945",
+      selectionForeground: "#ebdbb2",
+    },
+    [
+      "#282828", "#cc241d", "#98971a", "#d79921", "#458588", "#b16286", "#689d6a", "#a89984",
+      "#928374", "#fb4934", "#b8bb26", "#fabd2f", "#83a598", "#d3869b", "#8ec07c", "#ebdbb2",
+    ]
+  ),
+  nord: preset(
+    {
+      background: "#2e3440",
+      foreground: "#d8dee9",
+      cursor: "#d8dee9",
+      selectionBackground: "#434c5e",
+      selectionForeground: "#d8dee9",
+    },
+    [
+      "#3b4252", "#bf616a", "#a3be8c", "#ebcb8b", "#81a1c1", "#b48ead", "#88c0d0", "#e5e9f0",
+      "#4c566a", "#bf616a", "#a3be8c", "#ebcb8b", "#81a1c1", "#b48ead", "#8fbcbb", "#eceff4",
+    ]
+  ),
+  oneDark: preset(
+    {
+      background: "#282c34",
+      foreground: "#abb2bf",
+      cursor: "#528bff",
+      selectionBackground: "#3e4451",
+      selectionForeground: "#abb2bf",
+    },
+    [
+      "#282c34", "#e06c75", "#98c379", "#e5c07b", "#61afef", "#c678dd", "#56b6c2", "#abb2bf",
+      "#5c6370", "#e06c75", "#98c379", "#e5c07b", "#61afef", "#c678dd", "#56b6c2", "#ffffff",
+    ]
+  ),
+  catppuccinMocha: preset(
+    {
+      background: "#1e1e2e",
+      foreground: "#cdd6f4",
+      cursor: "#f5e0dc",
+      selectionBackground: "#585b70",
+      selectionForeground: "#cdd6f4",
+    },
+    [
+      "#45475a", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#bac2de",
+      "#585b70", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#a6adc8",
+    ]
+  ),
+  githubLight: preset(
+    {
+      background: "#ffffff",
+      foreground: "#1f2328",
+      cursor: "#0969da",
+      selectionBackground: "#b6e3ff",
+      selectionForeground: "#1f2328",
+    },
+    [
+      "#24292f", "#cf222e", "#116329", "#4d2d00", "#0969da", "#8250df", "#1b7c83", "#6e7781",
+      "#57606a", "#a40e26", "#1a7f37", "#633c01", "#218bff", "#a475f9", "#3192aa", "#8c959f",
+    ]
+  ),
+} satisfies Record<string, GhosttyTheme>;
+
+export type ThemePresetName = keyof typeof THEME_PRESETS;
+
+/** `#rrggbb` from `#rgb`, `rrggbb` or `#rrggbbaa` (alpha dropped); null when it isn't a hex color. */
+function hexColor(value: string): string | null {
+  let hex = value.trim().replace(/^#/, "").toLowerCase();
+  if (/^[0-9a-f]{3}$/.test(hex)) hex = [...hex].map((digit) => digit + digit).join("");
+  if (/^[0-9a-f]{8}$/.test(hex)) hex = hex.slice(0, 6);
+  return /^[0-9a-f]{6}$/.test(hex) ? `#${hex}` : null;
+}
+
+/**
+ * Fill what a theme file left out: the cursor and selected text follow the
+ * foreground, and anything else comes from the default theme.
+ */
+function completeTheme(colors: Partial<GhosttyTheme>, format: string): GhosttyTheme {
+  if (!colors.background || !colors.foreground) {
+    throw new Error(`${format} theme has no background or foreground color.`);
+  }
+  return resolveTheme({
+    cursor: colors.foreground,
+    selectionForeground: colors.foreground,
+    ...colors,
+  });
+}
+
+const GHOSTTY_KEYS: Record<string, keyof GhosttyTheme> = {
+  background: "background",
+  foreground: "foreground",
+  "cursor-color": "cursor",
+  "selection-background": "selectionBackground",
+  "selection-foreground": "selectionForeground",
+};
+
+/** A Ghostty theme file: `background = 1a1b26`, `palette = 0=#15161e` and so on. */
+export function parseGhosttyTheme(text: string): GhosttyTheme {
+  const colors: Partial<GhosttyTheme> = {};
+  for (const line of text.split(/\r?\n/)) {
+    const match = line.match(/^\s*([\w-]+)\s*=\s*(.*?)\s*$/);
+    if (!match) continue;
+    const [, key, value] = match;
+    if (key === "palette") {
+      const entry = value.match(/^(\d+)\s*=\s*(\S+)$/);
+      const index = entry ? Number(entry[1]) : -1;
+      const color = entry ? hexColor(entry[2]) : null;
+      if (color && index < ANSI_THEME_KEYS.length) colors[ANSI_THEME_KEYS[index]] = color;
+    } else if (GHOSTTY_KEYS[key]) {
+      const color = hexColor(value);
+      if (color) colors[GHOSTTY_KEYS[key]] = color;
+    }
+  }
+  return completeTheme(colors, "Ghostty");
+}
+
+const ITERM_KEYS: Record<string, keyof GhosttyTheme> = {
+  "Background Color": "background",
+  "Foreground Color": "foreground",
+  "Cursor Color": "cursor",
+  "Selection Color": "selectionBackground",
+  "Selected Text Color": "selectionForeground",
+};
+
+/** An iTerm2 `.itermcolors` property list. Component values are taken as sRGB. */
+export function parseItermColors(xml: string): GhosttyTheme {
+  const colors: Partial<GhosttyTheme> = {};
+  const entries = xml.matchAll(/<key>([^<]+)<\/key>\s*<dict>([\s\S]*?)<\/dict>/g);
+  for (const [, name, body] of entries) {
+    const ansi = name.match(/^Ansi (\d+) Color$/);
+    const key = ansi ? ANSI_THEME_KEYS[Number(ansi[1])] : ITERM_KEYS[name];
+    if (!key) continue;
+    const component = (channel: string) => {
+      const match = body.match(new RegExp(`<key>${channel} Component</key>\\s*<(?:real|integer)>([^<]+)<`));
+      const value = match ? Number.parseFloat(match[1]) : 0;
+      return Math.round(Math.min(1, Math.max(0, value)) * 255)
+        .toString(16)
+        .padStart(2, "0");
+    };
+    colors[key] = `#${component("Red")}${component("Green")}${component("Blue")}`;
+  }
+  return completeTheme(colors, "iTerm2");
+}
+
+/** Windows Terminal names for the ANSI colors, in ANSI order. */
+const WINDOWS_TERMINAL_PALETTE = [
+  "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+  "brightBlack", "brightRed", "brightGreen", "brightYellow", "brightBlue", "brightPurple", "brightCyan", "brightWhite",
+];
+
+/**
+ * A Windows Terminal color scheme, given as the scheme itself or as a
+ * settings file with a `schemes` array (picking `name`, or the first one).
+ * Accepts the JSON text or the parsed object.
+ */
+export function parseWindowsTerminalScheme(input: string | object, name?: string): GhosttyTheme {
+  const json = (typeof input === "string" ? JSON.parse(input) : input) as Record<string, unknown>;
+  let scheme = json;
+  if (Array.isArray(json.schemes)) {
+    const schemes = json.schemes as Record<string, unknown>[];
+    const found = name === undefined ? schemes[0] : schemes.find((candidate) => candidate.name === name);
+    if (!found) throw new Error(`Windows Terminal settings have no scheme named "${name ?? ""}".`);
+    scheme = found;
+  }
+  const read = (key: string) => (typeof scheme[key] === "string" ? hexColor(scheme[key] as string) : null);
+
+  const colors: Partial<GhosttyTheme> = {};
+  const assign = (key: keyof GhosttyTheme, color: string | null) => {
+    if (color) colors[key] = color;
+  };
+  assign("background", read("background"));
+  assign("foreground", read("foreground"));
+  assign("cursor", read("cursorColor"));
+  assign("selectionBackground", read("selectionBackground"));
+  WINDOWS_TERMINAL_PALETTE.forEach((key, index) => assign(ANSI_THEME_KEYS[index], read(key)));
+  return completeTheme(colors, "Windows Terminal");
+}
+
+/**
+ * A base16 scheme (`base00: "1a1b26"` … `base0F`, flat or under `palette:`),
+ * mapped onto the terminal the way base16-shell does.
+ */
+export function parseBase16Yaml(yaml: string): GhosttyTheme {
+  const base: Record<string, string> = {};
+  for (const match of yaml.matchAll(/^\s*(base0[0-9a-f])\s*:\s*["']?(#?[0-9a-f]{6})["']?/gim)) {
+    const color = hexColor(match[2]);
+    if (color) base[match[1].toLowerCase()] = color;
+  }
+  const missing = Array.from({ length: 16 }, (_, i) => `base0${i.toString(16)}`).filter((key) => !base[key]);
+  if (missing.length > 0) {
+    throw new Error(`base16 theme is missing ${missing.join(", ")}.`);
+  }
+  return preset(
+    {
+      background: base.base00,
+      foreground: base.base05,
+      cursor: base.base05,
+      selectionBackground: base.base02,
+      selectionForeground: base.base05,
+    },
+    [
+      base.base00, base.base08, base.base0b, base.base0a, base.base0d, base.base0e, base.base0c, base.base05,
+      base.base03, base.base08, base.base0b, base.base0a, base.base0d, base.base0e, base.base0c, base.base07,
+    ]
+  );
+}
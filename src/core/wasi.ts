@@ -8,43 +8,317 @@
  *   - proc_exit
  *   - environ_get / environ_sizes_get
  *   - args_get / args_sizes_get
+ *   - clock_time_get / clock_res_get (realtime, monotonic, approximate CPU time)
  */
 
-import type { WasiOptions } from "../types.js";
+import type {
+  TuiGuestSnapshot,
+  TuiStdinSource,
+  WasiBridgeState,
+  WasiClockSource,
+  WasiOptions,
+  WasiStdinChunk,
+} from "../types.js";
+import {
+  createSeededRandom,
+  createVirtualClock,
+  type SeededRandom,
+  type VirtualClock,
+} from "./determinism.js";
+import { loadBytes } from "./load.js";
 
 const WASI_ESUCCESS = 0;
 const WASI_EAGAIN = 6;
 const WASI_BADF = 8;
+const WASI_EINVAL = 28;
 const WASI_EVENTTYPE_CLOCK = 0;
 const WASI_EVENTTYPE_FD_READ = 1;
+const WASI_SUBCLOCKFLAGS_ABSTIME = 1;
+const WASI_EVENTRWFLAGS_FD_READWRITE_HANGUP = 1;
+
+const WASI_CLOCK_REALTIME = 0;
+const WASI_CLOCK_MONOTONIC = 1;
+const WASI_CLOCK_PROCESS_CPUTIME_ID = 2;
+const WASI_CLOCK_THREAD_CPUTIME_ID = 3;
 
 const STDIN_FD = 0;
 const STDOUT_FD = 1;
 const STDERR_FD = 2;
 
+const WASM_PAGE_SIZE = 65_536;
+
+/** Export a guest provides to be re-entered, in place of `_start`, on a restored session. */
+export const RESUME_EXPORT = "tui_preview_resume";
+
 /** Feature-detect JSPI (WebAssembly.Suspending / WebAssembly.promising). */
 export const hasJSPI =
   typeof WebAssembly.Suspending === "function" &&
   typeof WebAssembly.promising === "function";
 
+/**
+ * Default clock source: `Date.now()` for wall time and `performance.now()`
+ * for sub-millisecond monotonic time.
+ */
+export function createDefaultClock(): WasiClockSource {
+  return {
+    realtime: () => BigInt(Date.now()) * 1_000_000n,
+    monotonic: () => BigInt(Math.round(performance.now() * 1_000_000)),
+    realtimeResolution: 1_000_000n,
+    monotonicResolution: 1_000n,
+  };
+}
+
 export class WasiBridge {
-  private inputQueue: Uint8Array[] = [];
+  /** Replies (`response`) are delivered ahead of user input, even while it's paused. */
+  private inputQueue: WasiStdinChunk[] = [];
+  /** Set by closeInput(); once the queue drains, fd_read reports EOF. */
+  private inputClosed = false;
+  /** Set by pauseInput(); queued user input is held back until resumeInput(). */
+  private inputPaused = false;
+  /** Set once fd_read has reported EOF to the guest. */
+  private eofRead = false;
+  /** Exit code requested by kill(); the next syscall unwinds the guest. */
+  private killedWith: number | null = null;
   private memory!: WebAssembly.Memory;
   /** Resolvers waiting for input to arrive (used by suspending poll_oneoff). */
   private inputWaiters: Array<() => void> = [];
+  /** Set once the guest calls proc_exit. */
+  private exited = false;
+  /** True while the guest is suspended in poll_oneoff with no readable stdin. */
+  private blockedOnInput = false;
+  /** Resolvers for whenIdle(). */
+  private idleWaiters: Array<() => void> = [];
+  private readonly clock: WasiClockSource;
+  /** Set in deterministic mode; advanced by the clock timeouts the guest polls on. */
+  private readonly virtualClock: VirtualClock | null = null;
+  /** Set in deterministic mode; replaced when a saved state is restored. */
+  private seededRandom: SeededRandom | null = null;
+  private fillRandom: (target: Uint8Array) => void;
+  private readonly startedAt: bigint;
+  /** Controllers for streams handed out by stdoutStream() / stderrStream(). */
+  private readonly outputControllers = {
+    stdout: new Set<ReadableStreamDefaultController<Uint8Array>>(),
+    stderr: new Set<ReadableStreamDefaultController<Uint8Array>>(),
+  };
+  /** Monotonic time spent suspended in poll_oneoff, excluded from CPU time. */
+  private idleNs = 0n;
 
-  constructor(private opts: WasiOptions) {}
+  constructor(private opts: WasiOptions) {
+    if (opts.clock && opts.determinism) {
+      throw new Error("WasiOptions.clock can't be combined with determinism, which brings its own virtual clock.");
+    }
+    if (opts.determinism) {
+      this.virtualClock = createVirtualClock(opts.determinism.epochMs);
+      this.seededRandom = createSeededRandom(opts.determinism.seed ?? 1);
+      this.fillRandom = this.seededRandom;
+    } else {
+      this.fillRandom = (target) => crypto.getRandomValues(target);
+    }
+    this.clock = opts.clock ?? this.virtualClock ?? createDefaultClock();
+    this.startedAt = this.clock.monotonic();
+  }
 
   /** Push keyboard data from the terminal into the app's stdin */
   pushInput(data: string | Uint8Array) {
+    this.enqueueInput(data, false);
+  }
+
+  /**
+   * Push the terminal's reply to a query the guest wrote (DA, DSR, ...).
+   * It is read ahead of queued user input, and even while input is paused,
+   * so a guest waiting on the answer doesn't stall offscreen.
+   */
+  pushResponse(data: string | Uint8Array) {
+    this.enqueueInput(data, true);
+  }
+
+  private enqueueInput(data: string | Uint8Array, response: boolean) {
+    // Closing stdin ends user input; the guest may still be waiting on a reply.
+    if (this.inputClosed && !response) return;
     const chunk = typeof data === "string" ? new TextEncoder().encode(data) : data;
-    this.inputQueue.push(chunk);
+    this.inputQueue.push({ data: chunk, response });
+    if (!response) this.opts.onInput?.(chunk);
+    this.wakeInputWaiters();
+  }
+
+  /** Signal end-of-input: queued bytes are still delivered, then reads return 0 bytes. */
+  closeInput() {
+    if (this.inputClosed) return;
+    this.inputClosed = true;
+    this.wakeInputWaiters();
+  }
+
+  get isInputClosed() {
+    return this.inputClosed;
+  }
+
+  /** Whether the guest has drained closed stdin and been told it's at EOF. */
+  get hasReadEof() {
+    return this.eofRead;
+  }
+
+  /** State to carry into a new bridge with restoreState(), e.g. for a saved session. */
+  saveState(): WasiBridgeState {
+    return {
+      input: this.inputQueue.map((chunk) => ({ data: chunk.data.slice(), response: chunk.response })),
+      inputClosed: this.inputClosed,
+      virtualTimeNs: this.virtualClock?.monotonic() ?? null,
+      randomState: this.seededRandom?.state ?? null,
+    };
+  }
+
+  /**
+   * Adopt a saved state before the guest runs: queued stdin is replaced, and
+   * in deterministic mode the virtual clock and PRNG continue where they were.
+   */
+  restoreState(state: WasiBridgeState) {
+    this.inputQueue = state.input.map((chunk) => ({ data: chunk.data.slice(), response: chunk.response }));
+    this.inputClosed = state.inputClosed;
+    if (this.virtualClock && state.virtualTimeNs !== null) {
+      this.virtualClock.advance(state.virtualTimeNs - this.virtualClock.monotonic());
+    }
+    if (this.seededRandom && state.randomState !== null) {
+      this.seededRandom = createSeededRandom(state.randomState);
+      this.fillRandom = this.seededRandom;
+    }
+    this.wakeInputWaiters();
+  }
+
+  /**
+   * Hold back user input (keys, scripts, casts), e.g. while the preview is
+   * offscreen; it keeps queueing. Replies from pushResponse() still go through.
+   */
+  pauseInput() {
+    this.inputPaused = true;
+  }
+
+  resumeInput() {
+    if (!this.inputPaused) return;
+    this.inputPaused = false;
+    this.wakeInputWaiters();
+  }
+
+  /**
+   * Terminate the app. The guest unwinds with a WasiExitError at its next
+   * syscall; a guest suspended in poll_oneoff is woken immediately. A guest
+   * that has already exited is left alone, so onExit only fires once.
+   */
+  kill(code = 137) {
+    if (this.killedWith !== null || this.exited) return;
+    this.killedWith = code;
+    this.closeOutputStreams();
+    this.opts.onExit(code);
+    this.wakeInputWaiters();
+    this.notifyIdle();
+  }
+
+  get isKilled() {
+    return this.killedWith !== null;
+  }
+
+  /**
+   * Resolves once the guest is idle: suspended in poll_oneoff waiting for
+   * stdin with nothing queued, or exited. Requires JSPI to ever block.
+   */
+  whenIdle(): Promise<void> {
+    if ((this.blockedOnInput && !this.inputReadable()) || this.exited || this.killedWith !== null) {
+      return Promise.resolve();
+    }
+    return new Promise<void>((resolve) => this.idleWaiters.push(resolve));
+  }
+
+  private notifyIdle() {
+    for (const resolve of this.idleWaiters.splice(0)) {
+      resolve();
+    }
+  }
+
+  private throwIfKilled() {
+    if (this.killedWith !== null) {
+      throw new WasiExitError(this.killedWith);
+    }
+  }
+
+  /** Stream of bytes the app writes to stdout from now on. Closes on exit. */
+  stdoutStream(): ReadableStream<Uint8Array> {
+    return this.createOutputStream("stdout");
+  }
+
+  /** Stream of bytes the app writes to stderr from now on. Closes on exit. */
+  stderrStream(): ReadableStream<Uint8Array> {
+    return this.createOutputStream("stderr");
+  }
+
+  private createOutputStream(kind: "stdout" | "stderr"): ReadableStream<Uint8Array> {
+    const controllers = this.outputControllers[kind];
+    let active: ReadableStreamDefaultController<Uint8Array> | null = null;
+    return new ReadableStream<Uint8Array>({
+      start(controller) {
+        active = controller;
+        controllers.add(controller);
+      },
+      cancel() {
+        if (active) controllers.delete(active);
+      },
+    });
+  }
+
+  private emitOutput(kind: "stdout" | "stderr", data: Uint8Array) {
+    for (const controller of this.outputControllers[kind]) {
+      controller.enqueue(data.slice());
+    }
+  }
+
+  /** Close the streams handed out by stdoutStream() / stderrStream(). */
+  closeOutputStreams() {
+    for (const controllers of [this.outputControllers.stdout, this.outputControllers.stderr]) {
+      for (const controller of controllers) {
+        controller.close();
+      }
+      controllers.clear();
+    }
+  }
+
+  private wakeInputWaiters() {
     // Wake any suspended poll_oneoff calls.
     for (const resolve of this.inputWaiters.splice(0)) {
       resolve();
     }
   }
 
+  /**
+   * Index of the next chunk fd_read may deliver. Replies go first, since the
+   * guest is waiting on them; user input follows unless it's paused.
+   */
+  private nextChunkIndex() {
+    const reply = this.inputQueue.findIndex((chunk) => chunk.response);
+    if (reply >= 0 || this.inputPaused) return reply;
+    return this.inputQueue.length > 0 ? 0 : -1;
+  }
+
+  /** stdin is readable: a deliverable chunk is queued, or EOF has been reached and input isn't paused. */
+  private inputReadable() {
+    return this.nextChunkIndex() >= 0 || (!this.inputPaused && this.inputClosed);
+  }
+
+  /** Write an fd_read event, flagging hangup once stdin is closed and drained. */
+  private writeFdReadEvent(view: DataView, evPtr: number, userdata: bigint) {
+    const queued = this.inputQueue
+      .filter((chunk) => !this.inputPaused || chunk.response)
+      .reduce((sum, chunk) => sum + chunk.data.length, 0);
+    view.setBigUint64(evPtr, userdata, true);
+    view.setUint16(evPtr + 8, 0, true); // error = 0
+    view.setUint8(evPtr + 10, WASI_EVENTTYPE_FD_READ);
+    view.setBigUint64(evPtr + 16, BigInt(queued), true);
+    view.setUint16(evPtr + 24, queued === 0 ? WASI_EVENTRWFLAGS_FD_READWRITE_HANGUP : 0, true);
+  }
+
+  private writeClockEvent(view: DataView, evPtr: number, userdata: bigint) {
+    view.setBigUint64(evPtr, userdata, true);
+    view.setUint16(evPtr + 8, 0, true); // error = 0
+    view.setUint8(evPtr + 10, WASI_EVENTTYPE_CLOCK);
+  }
+
   /** Attach the WASM instance's memory after instantiation */
   attachMemory(memory: WebAssembly.Memory) {
     this.memory = memory;
@@ -58,9 +332,70 @@ export class WasiBridge {
     return new Uint8Array(this.memory.buffer);
   }
 
+  /**
+   * Approximate CPU time: monotonic time since the bridge was created minus
+   * time the guest spent suspended waiting for input.
+   */
+  private cpuTime(): bigint {
+    const elapsed = this.clock.monotonic() - this.startedAt - this.idleNs;
+    return elapsed > 0n ? elapsed : 0n;
+  }
+
+  private readClock(id: number): bigint | null {
+    switch (id) {
+      case WASI_CLOCK_REALTIME:
+        return this.clock.realtime();
+      case WASI_CLOCK_MONOTONIC:
+        return this.clock.monotonic();
+      case WASI_CLOCK_PROCESS_CPUTIME_ID:
+      case WASI_CLOCK_THREAD_CPUTIME_ID:
+        return this.cpuTime();
+      default:
+        return null;
+    }
+  }
+
+  private clockResolution(id: number): bigint | null {
+    switch (id) {
+      case WASI_CLOCK_REALTIME:
+        return this.clock.realtimeResolution;
+      case WASI_CLOCK_MONOTONIC:
+      case WASI_CLOCK_PROCESS_CPUTIME_ID:
+      case WASI_CLOCK_THREAD_CPUTIME_ID:
+        return this.clock.monotonicResolution;
+      default:
+        return null;
+    }
+  }
+
+  /**
+   * Shortest relative timeout (ns) across the clock subscriptions, or -1n if
+   * there are none. Absolute timeouts are converted against the current clock.
+   */
+  /** Relative timeout of the clock subscription at `subPtr`, in ns. */
+  private clockTimeout(view: DataView, subPtr: number): bigint {
+    const timeout = view.getBigUint64(subPtr + 24, true);
+    if ((view.getUint16(subPtr + 40, true) & WASI_SUBCLOCKFLAGS_ABSTIME) === 0) return timeout;
+    const now = this.readClock(view.getUint32(subPtr + 16, true)) ?? 0n;
+    return timeout > now ? timeout - now : 0n;
+  }
+
+  private minClockTimeout(view: DataView, inPtr: number, nsubscriptions: number): bigint {
+    let clockTimeoutNs = -1n;
+    for (let i = 0; i < nsubscriptions; i++) {
+      const subPtr = inPtr + i * 48;
+      if (view.getUint8(subPtr + 8) !== WASI_EVENTTYPE_CLOCK) continue;
+      const timeout = this.clockTimeout(view, subPtr);
+      if (clockTimeoutNs < 0n || timeout < clockTimeoutNs) {
+        clockTimeoutNs = timeout;
+      }
+    }
+    return clockTimeoutNs;
+  }
+
   /** Wait until the input queue has data, or until the given timeout (ms). */
   private waitForInput(timeoutMs: number): Promise<void> {
-    if (this.inputQueue.length > 0) return Promise.resolve();
+    if (this.inputReadable() || this.killedWith !== null) return Promise.resolve();
     return new Promise<void>((resolve) => {
       const waiter = () => {
         clearTimeout(timer);
@@ -128,6 +463,7 @@ export class WasiBridge {
       },
 
       fd_write: (fd: number, iovsPtr: number, iovsLen: number, nwrittenPtr: number) => {
+        this.throwIfKilled();
         if (fd !== STDOUT_FD && fd !== STDERR_FD) return WASI_BADF;
         const view = this.view();
         const u8 = this.u8();
@@ -147,17 +483,26 @@ export class WasiBridge {
         }
         if (fd === STDOUT_FD) {
           this.opts.stdout(merged);
+          this.emitOutput("stdout", merged);
         } else {
           this.opts.stderr(merged);
+          this.emitOutput("stderr", merged);
         }
         view.setUint32(nwrittenPtr, nwritten, true);
         return WASI_ESUCCESS;
       },
 
       fd_read: (fd: number, iovsPtr: number, iovsLen: number, nreadPtr: number) => {
+        this.throwIfKilled();
         if (fd !== STDIN_FD) return WASI_BADF;
-        const chunk = this.inputQueue[0];
-        if (!chunk) return WASI_EAGAIN;
+        const index = this.nextChunkIndex();
+        if (index < 0) {
+          if (this.inputPaused || !this.inputClosed) return WASI_EAGAIN;
+          this.eofRead = true;
+          this.view().setUint32(nreadPtr, 0, true);
+          return WASI_ESUCCESS;
+        }
+        const chunk = this.inputQueue[index].data;
         const view = this.view();
         const u8 = this.u8();
         let nread = 0;
@@ -169,9 +514,9 @@ export class WasiBridge {
           nread += toCopy;
         }
         if (nread >= chunk.length) {
-          this.inputQueue.shift();
+          this.inputQueue.splice(index, 1);
         } else if (nread > 0) {
-          this.inputQueue[0] = chunk.subarray(nread);
+          this.inputQueue[index] = { ...this.inputQueue[index], data: chunk.subarray(nread) };
         }
         view.setUint32(nreadPtr, nread, true);
         return WASI_ESUCCESS;
@@ -186,32 +531,40 @@ export class WasiBridge {
       // Without JSPI: synchronous non-blocking check (legacy/replay mode).
       poll_oneoff: hasJSPI
         ? async (inPtr: number, outPtr: number, nsubscriptions: number, neventsPtr: number) => {
+              this.throwIfKilled();
               const view = this.view();
 
               // Check if any FD_READ subscription can be satisfied immediately.
               let hasFdRead = false;
-              let clockTimeoutNs = -1n;
               for (let i = 0; i < nsubscriptions; i++) {
-                const subPtr = inPtr + i * 48;
-                const type = view.getUint8(subPtr + 8);
-                if (type === WASI_EVENTTYPE_FD_READ) {
+                if (view.getUint8(inPtr + i * 48 + 8) === WASI_EVENTTYPE_FD_READ) {
                   hasFdRead = true;
                 }
-                if (type === WASI_EVENTTYPE_CLOCK) {
-                  const timeout = view.getBigUint64(subPtr + 24, true);
-                  if (clockTimeoutNs < 0n || timeout < clockTimeoutNs) {
-                    clockTimeoutNs = timeout;
-                  }
-                }
               }
+              const clockTimeoutNs = this.minClockTimeout(view, inPtr, nsubscriptions);
 
               // If there's an FD_READ subscription and no data yet, suspend.
-              if (hasFdRead && this.inputQueue.length === 0) {
+              if (hasFdRead && !this.inputReadable()) {
                 const timeoutMs =
                   clockTimeoutNs >= 0n
                     ? Number(clockTimeoutNs / 1_000_000n)
                     : 60_000;
-                await this.waitForInput(Math.max(1, Math.min(timeoutMs, 60_000)));
+                const waitStart = this.clock.monotonic();
+                const waiting = this.waitForInput(Math.max(1, Math.min(timeoutMs, 60_000)));
+                this.blockedOnInput = true;
+                this.notifyIdle();
+                try {
+                  await waiting;
+                } finally {
+                  this.blockedOnInput = false;
+                }
+                this.idleNs += this.clock.monotonic() - waitStart;
+                this.throwIfKilled();
+              }
+
+              // Clock events are reported below, so virtual time moves by the timeout.
+              if (clockTimeoutNs >= 0n) {
+                this.virtualClock?.advance(clockTimeoutNs);
               }
 
               // Now fill out the events.
@@ -220,18 +573,12 @@ export class WasiBridge {
                 const subPtr = inPtr + i * 48;
                 const type = view.getUint8(subPtr + 8);
                 const userdata = view.getBigUint64(subPtr, true);
-                if (type === WASI_EVENTTYPE_FD_READ && this.inputQueue.length > 0) {
-                  const evPtr = outPtr + nevents * 32;
-                  view.setBigUint64(evPtr, userdata, true);
-                  view.setUint16(evPtr + 8, 0, true); // error = 0
-                  view.setUint8(evPtr + 10, type);
+                if (type === WASI_EVENTTYPE_FD_READ && this.inputReadable()) {
+                  this.writeFdReadEvent(view, outPtr + nevents * 32, userdata);
                   nevents++;
                 } else if (type === WASI_EVENTTYPE_CLOCK) {
                   // Clock subscriptions always fire (we already waited).
-                  const evPtr = outPtr + nevents * 32;
-                  view.setBigUint64(evPtr, userdata, true);
-                  view.setUint16(evPtr + 8, 0, true);
-                  view.setUint8(evPtr + 10, type);
+                  this.writeClockEvent(view, outPtr + nevents * 32, userdata);
                   nevents++;
                 }
               }
@@ -240,30 +587,45 @@ export class WasiBridge {
             }
         : (inPtr: number, outPtr: number, nsubscriptions: number, neventsPtr: number) => {
             // Fallback: synchronous non-blocking check (no JSPI).
+            this.throwIfKilled();
             const view = this.view();
+            const clockTimeoutNs = this.minClockTimeout(view, inPtr, nsubscriptions);
             let nevents = 0;
             for (let i = 0; i < nsubscriptions; i++) {
               const subPtr = inPtr + i * 48;
               const type = view.getUint8(subPtr + 8);
-              if (type === WASI_EVENTTYPE_FD_READ && this.inputQueue.length > 0) {
-                const evPtr = outPtr + nevents * 32;
-                view.setBigUint64(evPtr, view.getBigUint64(subPtr, true), true);
-                view.setUint16(evPtr + 8, 0, true);
-                view.setUint8(evPtr + 10, type);
+              const userdata = view.getBigUint64(subPtr, true);
+              if (type === WASI_EVENTTYPE_FD_READ && this.inputReadable()) {
+                this.writeFdReadEvent(view, outPtr + nevents * 32, userdata);
+                nevents++;
+              } else if (
+                type === WASI_EVENTTYPE_CLOCK &&
+                this.virtualClock &&
+                this.clockTimeout(view, subPtr) <= clockTimeoutNs
+              ) {
+                // Virtual time jumps to the earliest timeout below, so it has elapsed.
+                this.writeClockEvent(view, outPtr + nevents * 32, userdata);
                 nevents++;
               }
             }
+            if (clockTimeoutNs >= 0n) {
+              this.virtualClock?.advance(clockTimeoutNs);
+            }
             view.setUint32(neventsPtr, nevents, true);
             return WASI_ESUCCESS;
           },
 
       proc_exit: (code: number) => {
+        this.throwIfKilled();
+        this.closeOutputStreams();
+        this.exited = true;
         this.opts.onExit(code);
+        this.notifyIdle();
         throw new WasiExitError(code);
       },
 
       random_get: (bufPtr: number, bufLen: number) => {
-        crypto.getRandomValues(new Uint8Array(this.memory.buffer, bufPtr, bufLen));
+        this.fillRandom(new Uint8Array(this.memory.buffer, bufPtr, bufLen));
         return WASI_ESUCCESS;
       },
 
@@ -278,12 +640,22 @@ export class WasiBridge {
       fd_prestat_get: () => WASI_BADF,
       fd_prestat_dir_name: () => WASI_BADF,
       path_open: () => WASI_BADF,
-      sched_yield: () => WASI_ESUCCESS,
-      clock_time_get: (_id: number, _precision: bigint, timePtr: number) => {
-        const ns = BigInt(Date.now()) * 1_000_000n;
+      sched_yield: () => {
+        this.throwIfKilled();
+        return WASI_ESUCCESS;
+      },
+      clock_time_get: (id: number, _precision: bigint, timePtr: number) => {
+        const ns = this.readClock(id);
+        if (ns === null) return WASI_EINVAL;
         this.view().setBigUint64(timePtr, ns, true);
         return WASI_ESUCCESS;
       },
+      clock_res_get: (id: number, resPtr: number) => {
+        const res = this.clockResolution(id);
+        if (res === null) return WASI_EINVAL;
+        this.view().setBigUint64(resPtr, res, true);
+        return WASI_ESUCCESS;
+      },
     };
   }
 
@@ -303,25 +675,102 @@ export class WasiExitError extends Error {
   }
 }
 
+/**
+ * Feed a stdin source into the bridge, optionally closing input afterwards.
+ * A ReadableStream can only be consumed once; a locked stream is skipped.
+ */
+export async function feedStdin(
+  bridge: WasiBridge,
+  source: TuiStdinSource,
+  options: { close?: boolean; signal?: AbortSignal } = {}
+): Promise<void> {
+  if (typeof source === "string" || source instanceof Uint8Array) {
+    bridge.pushInput(source);
+  } else if (!source.locked) {
+    const reader = source.getReader();
+    const onAbort = () => void reader.cancel().catch(() => {});
+    options.signal?.addEventListener("abort", onAbort, { once: true });
+    try {
+      while (!options.signal?.aborted) {
+        const { done, value } = await reader.read();
+        if (done) break;
+        bridge.pushInput(value);
+      }
+    } finally {
+      options.signal?.removeEventListener("abort", onAbort);
+      reader.releaseLock();
+    }
+  }
+  if (options.close && !options.signal?.aborted) {
+    bridge.closeInput();
+  }
+}
+
 /** Compiled module cache — keyed by URL string, persists for the page lifetime. */
 const moduleCache = new Map<string, WebAssembly.Module>();
 
+export interface WasiApp {
+  /** Run `_start`, or the resume export when instantiated with `restore` */
+  run: () => Promise<void>;
+  /** Copy linear memory and exported globals, e.g. while the guest waits for input */
+  snapshot: () => TuiGuestSnapshot;
+  /** Whether the guest exports `tui_preview_resume`, so its snapshots can be restored */
+  resumable: boolean;
+}
+
+export interface InstantiateAppOptions {
+  /**
+   * Guest state to load before running. The call stack isn't part of a
+   * snapshot, so `run` enters through the `tui_preview_resume` export, which
+   * must continue from state the guest keeps in linear memory.
+   */
+  restore?: TuiGuestSnapshot;
+}
+
+function snapshotGuest(instance: WebAssembly.Instance): TuiGuestSnapshot {
+  const memory = instance.exports.memory as WebAssembly.Memory;
+  const bytes = new Uint8Array(memory.buffer);
+  let end = bytes.length;
+  while (end > 0 && bytes[end - 1] === 0) end--;
+  const globals: Record<string, number | bigint> = {};
+  for (const [name, value] of Object.entries(instance.exports)) {
+    if (value instanceof WebAssembly.Global) {
+      globals[name] = value.value as number | bigint;
+    }
+  }
+  return { memorySize: bytes.length, memory: bytes.slice(0, end), globals };
+}
+
+function restoreGuest(instance: WebAssembly.Instance, snapshot: TuiGuestSnapshot) {
+  const memory = instance.exports.memory as WebAssembly.Memory;
+  const missing = snapshot.memorySize - memory.buffer.byteLength;
+  if (missing > 0) {
+    memory.grow(Math.ceil(missing / WASM_PAGE_SIZE));
+  }
+  const bytes = new Uint8Array(memory.buffer);
+  bytes.set(snapshot.memory);
+  bytes.fill(0, snapshot.memory.length, snapshot.memorySize);
+  for (const [name, value] of Object.entries(snapshot.globals)) {
+    const global = instance.exports[name];
+    if (!(global instanceof WebAssembly.Global)) continue;
+    try {
+      global.value = value;
+    } catch {
+      // Immutable global: already has its initial value.
+    }
+  }
+}
+
 /** Load and instantiate a WASM TUI app with a WasiBridge */
 export async function instantiateApp(
   source: string | URL,
-  bridge: WasiBridge
-): Promise<{ run: () => Promise<void> }> {
+  bridge: WasiBridge,
+  options: InstantiateAppOptions = {}
+): Promise<WasiApp> {
   const key = source.toString();
   let module = moduleCache.get(key);
   if (!module) {
-    const response = await fetch(source);
-    if (!response.ok) {
-      throw new Error(`Failed to load app wasm: ${response.status} ${response.statusText}`);
-    }
-    const bytes = await response.arrayBuffer();
-    if (bytes.byteLength === 0) {
-      throw new Error("App wasm is empty.");
-    }
+    const bytes = await loadBytes(source, "app wasm");
     module = await WebAssembly.compile(bytes);
     moduleCache.set(key, module);
   }
@@ -339,8 +788,18 @@ export async function instantiateApp(
   const instance = await WebAssembly.instantiate(module, importObject);
   bridge.attachMemory(instance.exports.memory as WebAssembly.Memory);
 
-  const rawStart = instance.exports._start as (() => void) | undefined;
-  if (!rawStart) throw new Error("WASM module has no _start export");
+  const resume = instance.exports[RESUME_EXPORT] as (() => void) | undefined;
+  const rawStart = options.restore ? resume : (instance.exports._start as (() => void) | undefined);
+  if (!rawStart) {
+    throw new Error(
+      options.restore
+        ? `WASM module has no ${RESUME_EXPORT} export, so a saved session can't be resumed`
+        : "WASM module has no _start export"
+    );
+  }
+  if (options.restore) {
+    restoreGuest(instance, options.restore);
+  }
 
   // With JSPI, wrap _start so it returns a Promise that resolves when the
   // WASM app finishes (or suspends on poll_oneoff and resumes later).
@@ -354,7 +813,11 @@ export async function instantiateApp(
         await _start();
       } catch (e) {
         if (!(e instanceof WasiExitError)) throw e;
+      } finally {
+        bridge.closeOutputStreams();
       }
     },
+    snapshot: () => snapshotGuest(instance),
+    resumable: typeof resume === "function",
   };
 }
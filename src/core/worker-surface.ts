@@ -0,0 +1,229 @@
+/**
+ * Worker-backed terminal surface: the canvas is transferred to a worker that
+ * runs libghostty and the renderer, so heavy output doesn't block the page.
+ * The main thread only forwards input and mirrors the last painted screen.
+ */
+
+import {
+  attachBasicInput,
+  loadTerminalFont,
+  measureCellSize,
+  normalizeEol,
+  pickFontOptions,
+  viewportToText,
+  watchDevicePixelRatio,
+  type MiniTerminalSurface,
+  type MiniTerminalSurfaceOptions,
+  type TerminalState,
+  type ViewportData,
+} from "./libghostty.js";
+import { pickColorOptions } from "./contrast.js";
+import { LiveRegion } from "./live-region.js";
+import type { RenderWorkerEvent, RenderWorkerRequest } from "./render-worker.js";
+
+/** Whether this browser can render in a worker (`OffscreenCanvas` plus `transferControlToOffscreen`). */
+export function supportsWorkerRendering(): boolean {
+  return (
+    typeof Worker !== "undefined" &&
+    typeof OffscreenCanvas !== "undefined" &&
+    typeof HTMLCanvasElement !== "undefined" &&
+    "transferControlToOffscreen" in HTMLCanvasElement.prototype
+  );
+}
+
+/**
+ * Like `createMiniTerminalSurface`, but terminal parsing and painting happen
+ * in a worker. `getText`, `getViewportData` and `getState` reflect the last
+ * frame the worker painted, and replies to terminal queries arrive through
+ * `onResponse` (or `drainResponses`) a moment after the write.
+ */
+export async function createWorkerTerminalSurface(
+  options: MiniTerminalSurfaceOptions
+): Promise<MiniTerminalSurface> {
+  const font = pickFontOptions(options);
+  await loadTerminalFont(options.fontSize, options.fontFamily, options.fontLoadTimeout, font);
+  let cols: number;
+  let rows: number;
+  let cell = measureCellSize(options.fontSize, options.fontFamily, font);
+  if (options.widthPx != null && options.heightPx != null) {
+    cols = Math.max(1, Math.floor(options.widthPx / cell.w));
+    rows = Math.max(1, Math.floor(options.heightPx / cell.h));
+  } else {
+    cols = options.cols ?? 80;
+    rows = options.rows ?? 24;
+  }
+
+  options.container.innerHTML = "";
+
+  const canvas = document.createElement("canvas");
+  canvas.style.display = "block";
+  canvas.style.outline = "none";
+  canvas.tabIndex = options.interactive ? 0 : -1;
+  const setCanvasSize = () => {
+    canvas.style.width = `${surface.cols * cell.w}px`;
+    canvas.style.height = `${surface.rows * cell.h}px`;
+  };
+  options.container.appendChild(canvas);
+  const offscreen = canvas.transferControlToOffscreen();
+  const liveRegion = new LiveRegion(options.container, options.announce ?? "rows");
+
+  const worker = new Worker(new URL("./render-worker.js", import.meta.url), { type: "module" });
+  const send = (request: RenderWorkerRequest, transfer: Transferable[] = []) =>
+    worker.postMessage(request, transfer);
+
+  let viewport: ViewportData = { cols, rows, buffer: new Uint8Array(0) };
+  let state: TerminalState | null = null;
+  let disposed = false;
+  const pendingResponses: string[] = [];
+  const pendingPngs = new Map<number, { resolve: (blob: Blob) => void; reject: (error: Error) => void }>();
+  let nextPngId = 0;
+
+  let settleReady: { resolve: () => void; reject: (error: Error) => void } | null = null;
+  const ready = new Promise<void>((resolve, reject) => {
+    settleReady = { resolve, reject };
+  });
+
+  worker.onmessage = (event: MessageEvent<RenderWorkerEvent>) => {
+    const message = event.data;
+    switch (message.type) {
+      case "ready":
+        cell = message.cellSize;
+        surface.cellSize = cell;
+        setCanvasSize();
+        settleReady?.resolve();
+        settleReady = null;
+        return;
+      case "cellSize":
+        cell = message.cellSize;
+        surface.cellSize = cell;
+        setCanvasSize();
+        options.onCellSizeChange?.(cell);
+        return;
+      case "error":
+        if (settleReady) {
+          settleReady.reject(new Error(message.message));
+          settleReady = null;
+        } else {
+          console.error(`tui-preview render worker: ${message.message}`);
+        }
+        return;
+      case "frame":
+        viewport = message.viewport;
+        state = message.state;
+        liveRegion.update(viewport);
+        return;
+      case "response":
+        if (options.onResponse) options.onResponse(message.data);
+        else pendingResponses.push(message.data);
+        return;
+      case "title":
+        options.onTitle?.(message.title);
+        return;
+      case "png": {
+        const pending = pendingPngs.get(message.id);
+        pendingPngs.delete(message.id);
+        if (!pending) return;
+        if (message.blob) pending.resolve(message.blob);
+        else pending.reject(new Error(message.message ?? "Failed to encode canvas as PNG."));
+        return;
+      }
+    }
+  };
+  worker.onerror = (event) => {
+    settleReady?.reject(new Error(event.message || "Render worker failed to start."));
+    settleReady = null;
+  };
+
+  const detachInput = options.interactive
+    ? attachBasicInput(canvas, (data) => options.onInput?.(data))
+    : () => {};
+  const stopWatchingDpr = watchDevicePixelRatio((devicePixelRatio) => {
+    if (!disposed) send({ type: "devicePixelRatio", devicePixelRatio });
+  });
+
+  const surface: MiniTerminalSurface = {
+    cols,
+    rows,
+    cellSize: cell,
+    write(text: string) {
+      if (disposed) return;
+      send({ type: "write", data: options.convertEol ? normalizeEol(text) : text });
+    },
+    resize(nextCols: number, nextRows: number) {
+      if (disposed) return;
+      surface.cols = Math.max(1, Math.floor(nextCols));
+      surface.rows = Math.max(1, Math.floor(nextRows));
+      setCanvasSize();
+      send({ type: "resize", cols: surface.cols, rows: surface.rows });
+    },
+    setSuspended(suspended: boolean) {
+      if (disposed) return;
+      send({ type: "suspend", suspended });
+    },
+    getText() {
+      return disposed ? "" : viewportToText(viewport);
+    },
+    getViewportData() {
+      return viewport;
+    },
+    getState() {
+      return disposed ? null : state;
+    },
+    toPng() {
+      if (disposed) return Promise.reject(new Error("Terminal surface has been disposed."));
+      const id = nextPngId++;
+      return new Promise<Blob>((resolve, reject) => {
+        pendingPngs.set(id, { resolve, reject });
+        send({ type: "png", id });
+      });
+    },
+    focus() {
+      canvas.focus();
+    },
+    drainResponses() {
+      return pendingResponses.splice(0);
+    },
+    dispose() {
+      if (disposed) return;
+      disposed = true;
+      detachInput();
+      stopWatchingDpr();
+      worker.terminate();
+      liveRegion.dispose();
+      for (const pending of pendingPngs.values()) {
+        pending.reject(new Error("Terminal surface has been disposed."));
+      }
+      pendingPngs.clear();
+      if (canvas.parentElement === options.container) {
+        options.container.removeChild(canvas);
+      }
+    },
+  };
+  setCanvasSize();
+
+  send(
+    {
+      type: "init",
+      canvas: offscreen,
+      cols,
+      rows,
+      fontSize: options.fontSize,
+      fontFamily: options.fontFamily,
+      font,
+      colors: pickColorOptions(options),
+      theme: options.theme,
+      wasmUrl: options.wasmUrl ? new URL(options.wasmUrl, document.baseURI).href : undefined,
+      devicePixelRatio: window.devicePixelRatio || 1,
+      showCursor: options.showCursor,
+      fontLoadTimeout: options.fontLoadTimeout,
+    },
+    [offscreen]
+  );
+  try {
+    await ready;
+  } catch (error) {
+    surface.dispose();
+    throw error;
+  }
+  return surface;
+}
@@ -1,16 +1,37 @@
+export { TuiPoster } from "./TuiPoster.js";
 export { TuiPreview } from "./TuiPreview.js";
 export type {
   GhosttyTheme,
   ResolvedTuiPreviewOptions,
+  TuiAnnounceVerbosity,
   TuiArgv,
+  TuiCastPlaybackOptions,
+  TuiDaltonizeMode,
+  TuiDeterminismOptions,
   TuiFitMode,
+  TuiGuestSnapshot,
+  TuiOutputCallback,
   TuiRenderMode,
+  TuiRenderer,
+  TuiPreviewCastProps,
   TuiPreviewCommonProps,
+  TuiPreviewHandle,
+  TuiPosterSnapshot,
   TuiPreviewModernProps,
   TuiPreviewProps,
   TuiPreviewStatus,
   TuiRuntimeSize,
+  TuiScriptOptions,
+  TuiScriptStep,
+  TuiSessionSnapshot,
+  TuiStartMode,
+  TuiStderrTarget,
+  TuiStdinSource,
   TuiTerminalOptions,
+  WasiBridgeState,
+  WasiClockSource,
   WasiOptions,
+  WasiStdinChunk,
 } from "./types.js";
+export { decodeSession, encodeSession } from "./core/session.js";
 export { WasiBridge, WasiExitError, instantiateApp } from "./core/wasi.js";
@@ -6,32 +6,127 @@ export interface TuiRuntimeSize {
 export type TuiArgv = string[] | ((size: TuiRuntimeSize) => string[]);
 export type TuiFitMode = "container" | "none";
 export type TuiRenderMode = "interactive" | "static";
-export type TuiPreviewStatus = "loading" | "running" | "exited" | "error";
+export type TuiPreviewStatus = "idle" | "loading" | "running" | "exited" | "error";
+export type TuiStartMode = "eager" | "visible" | "click";
+export type TuiStderrTarget = "terminal" | "panel" | "console";
+export type TuiRenderer = "canvas" | "dom";
+/** Screen reader announcements: every changed row, only the bottom-most changed row, or none. */
+export type TuiAnnounceVerbosity = "rows" | "line" | "off";
+/** Color-vision deficiency to recolor for. */
+export type TuiDaltonizeMode = "none" | "protanopia" | "deuteranopia" | "tritanopia";
+export type TuiOutputCallback = (data: Uint8Array, text: string) => void;
+export type TuiStdinSource = string | Uint8Array | ReadableStream<Uint8Array | string>;
 
 export interface TuiTerminalOptions {
   /** Font size in pixels. Default: 14 */
   fontSize?: number;
   /** CSS font family. Default: monospace */
   fontFamily?: string;
+  /** Family for bold text, e.g. a dedicated heavy face. Default: `fontFamily` */
+  fontFamilyBold?: string;
+  /** Family for italic text. Default: `fontFamily` */
+  fontFamilyItalic?: string;
+  /** Families tried in order for glyphs the main font lacks, e.g. a Nerd Font symbol font, then an emoji font. Default: [] */
+  fallbackFonts?: string[];
+  /** Cell height as a multiple of the font's height. Default: 1 */
+  lineHeight?: number;
+  /** Extra pixels added to each cell's width; may be negative. Default: 0 */
+  letterSpacing?: number;
+  /** Let programming fonts join `->`, `!=` and friends into ligatures. Default: false */
+  ligatures?: boolean;
+  /**
+   * Lighten or darken text until it reaches this WCAG contrast ratio (1–21)
+   * against its cell's background, e.g. 4.5 for dim help lines. Default: 1 (off)
+   */
+  minimumContrastRatio?: number;
+  /** Shift colors so they stay distinguishable with a color-vision deficiency. Default: "none" */
+  daltonize?: TuiDaltonizeMode;
   /** Terminal color theme overrides */
   theme?: Partial<GhosttyTheme>;
   /** URL to libghostty-vt wasm. Default: "/ghostty-vt.wasm" */
   wasmUrl?: string | URL;
   /** Convert LF to CRLF. Default: true */
   convertEol?: boolean;
+  /** "dom" renders rows of spans that screen readers and find-in-page can read. Default: "canvas" */
+  renderer?: TuiRenderer;
+  /** What the hidden live region reads out as the screen changes. Default: "rows" */
+  announce?: TuiAnnounceVerbosity;
+  /**
+   * Parse and paint in a Web Worker via OffscreenCanvas so heavy output doesn't
+   * block the page. Ignored where unsupported. Default: false
+   */
+  worker?: boolean;
+  /**
+   * Milliseconds to wait for a web font before measuring cells with a
+   * fallback; the grid is re-measured when it arrives. Default: 3000
+   */
+  fontLoadTimeout?: number;
+}
+
+/**
+ * One step of an autoplay script. Strings use the textual form:
+ * `type "hello"`, `press Down`, `wait 500ms`, `waitForText "Game Over"`.
+ * `delay` is per typed character and `timeout` defaults to 10000 (ms).
+ */
+export type TuiScriptStep =
+  | string
+  | { type: string; delay?: number }
+  | { press: string | string[] }
+  | { wait: number }
+  | { waitForText: string | RegExp; timeout?: number };
+
+export interface TuiScriptOptions {
+  steps: TuiScriptStep[];
+  /** Restart the app and replay when the script finishes. Default: false */
+  loop?: boolean;
+  /** Stop the script and hand control to the user on their first keypress. Default: true */
+  handOffOnInput?: boolean;
+  /** Default delay between typed characters (ms). Default: 50 */
+  typingDelay?: number;
+}
+
+export interface TuiDeterminismOptions {
+  /** Seed for the PRNG behind random_get. Default: 1 */
+  seed?: number;
+  /** Virtual wall-clock start, in ms since the Unix epoch. Default: 0 */
+  epochMs?: number;
 }
 
 export interface TuiPreviewCommonProps {
   /** Environment variables (TERM, COLORTERM are set automatically) */
   env?: Record<string, string>;
+  /** Seeded randomness and virtual time for reproducible output. Default: off */
+  determinism?: boolean | TuiDeterminismOptions;
+  /** Data pre-fed into the app's stdin. A ReadableStream is consumed by the first run only. */
+  stdin?: TuiStdinSource;
+  /** Close stdin (EOF) once `stdin` has been fed. Default: false */
+  closeStdin?: boolean;
+  /** Input script played automatically once the app starts. Read when the app (re)starts. */
+  script?: TuiScriptStep[] | TuiScriptOptions;
   /** Whether the user can type into the terminal. Default: true */
   interactive?: boolean;
+  /** When to load and start: immediately, once scrolled into view, or on click. Default: "eager" */
+  start?: TuiStartMode;
+  /** Static screen rendered until the live terminal is running (SSR-safe) */
+  poster?: TuiPosterSnapshot;
+  /** Where stderr is shown: the terminal, a collapsible panel, or the browser console. Default: "terminal" */
+  stderr?: TuiStderrTarget;
+  /** Called with raw bytes and decoded text for every stdout write */
+  onStdout?: TuiOutputCallback;
+  /** Called with raw bytes and decoded text for every stderr write */
+  onStderr?: TuiOutputCallback;
+  /** Record the session to asciicast v2 from the moment the app starts. Default: false */
+  record?: boolean;
+  /** Called with the asciicast v2 document when a recording stops or the app exits */
+  onRecordingComplete?: (cast: string) => void;
   /** Called when the app exits */
   onExit?: (code: number) => void;
   /** Called on runtime errors */
   onError?: (error: unknown) => void;
   /** Called whenever status changes */
   onStatusChange?: (status: TuiPreviewStatus) => void;
+  /** Called when the app sets the terminal title (OSC 0/2) */
+  onTitleChange?: (title: string) => void;
   className?: string;
   style?: React.CSSProperties;
 }
@@ -39,6 +134,8 @@ export interface TuiPreviewCommonProps {
 export interface TuiPreviewModernProps extends TuiPreviewCommonProps {
   /** URL or path to a wasm32-wasi binary */
   wasm: string | URL;
+  cast?: undefined;
+  playback?: undefined;
   /** CLI argv (without argv[0]), static or size-aware */
   argv?: TuiArgv;
   /** Render mode. Default: "interactive" */
@@ -49,9 +146,140 @@ export interface TuiPreviewModernProps extends TuiPreviewCommonProps {
   size?: TuiRuntimeSize;
   /** Terminal renderer options */
   terminal?: TuiTerminalOptions;
+  /** Saved session to resume instead of starting fresh; see `saveSession()`. Read when the app starts. */
+  session?: TuiSessionSnapshot;
 }
 
-export type TuiPreviewProps = TuiPreviewModernProps;
+/** Precomputed screen shown before the app starts, and in server-rendered HTML. */
+export type TuiPosterSnapshot =
+  | {
+      cols: number;
+      rows: number;
+      /** Base64 viewport cell buffer, as produced by `capturePoster` */
+      cells: string;
+    }
+  | {
+      cols?: number;
+      rows?: number;
+      /** ANSI text; newlines, tabs and SGR colors are rendered */
+      ansi: string;
+    };
+
+/** Guest linear memory and exported globals, from `instantiateApp(...).snapshot()`. */
+export interface TuiGuestSnapshot {
+  /** Memory size in bytes */
+  memorySize: number;
+  /** Memory contents up to the last non-zero byte */
+  memory: Uint8Array;
+  /** Exported global values by export name; immutable ones are skipped on restore */
+  globals: Record<string, number | bigint>;
+}
+
+/** WasiBridge state that outlives an instance. stdin is the only descriptor with state. */
+/** A piece of queued, unread stdin. */
+export interface WasiStdinChunk {
+  data: Uint8Array;
+  /** Terminal reply to a guest query (DA, DSR, ...) rather than user input */
+  response: boolean;
+}
+
+export interface WasiBridgeState {
+  /** Queued, unread stdin, in arrival order */
+  input: WasiStdinChunk[];
+  inputClosed: boolean;
+  /** Virtual clock time in ns (deterministic mode), else null */
+  virtualTimeNs: bigint | null;
+  /** Seeded PRNG state (deterministic mode), else null */
+  randomState: number | null;
+}
+
+/** A paused guest, its WASI state and its screen, restorable into a new instance. */
+export interface TuiSessionSnapshot {
+  version: 1;
+  /** App source the memory belongs to; restoring into another app is refused */
+  wasm: string;
+  cols: number;
+  rows: number;
+  guest: TuiGuestSnapshot;
+  bridge: WasiBridgeState;
+  /** Screen, cursor and modes as ANSI, from `serializeScreen` */
+  terminal: string;
+}
+
+export interface TuiCastPlaybackOptions {
+  /** Start playing once loaded. Default: true */
+  autoplay?: boolean;
+  /** Playback speed multiplier. Default: 1 */
+  speed?: number;
+  /** Restart from the beginning when finished. Default: false */
+  loop?: boolean;
+  /** Cap on idle gaps between events, in seconds. Default: from the cast header */
+  idleTimeLimit?: number;
+  /** Show play/pause, seek and speed controls. Default: true */
+  controls?: boolean;
+}
+
+export interface TuiPreviewCastProps extends Omit<TuiPreviewModernProps, "wasm" | "argv" | "session"> {
+  wasm?: undefined;
+  argv?: undefined;
+  session?: undefined;
+  /** URL or path to an asciicast v2 recording, played instead of a wasm app */
+  cast: string | URL;
+  /** Recording playback options */
+  playback?: TuiCastPlaybackOptions;
+}
+
+export type TuiPreviewProps = TuiPreviewModernProps | TuiPreviewCastProps;
+
+/** Imperative handle exposed through the `ref` of `TuiPreview`. */
+export interface TuiPreviewHandle {
+  /** Send raw data to the app's stdin */
+  sendInput(data: string | Uint8Array): void;
+  /** Send named keys, e.g. `["h", "i", "Enter", "Ctrl+C", "Down"]`; throws, sending nothing, on an unknown key */
+  sendKeys(keys: string[]): void;
+  /** Restart the app from scratch */
+  restart(): void;
+  /** Terminate the app. Default exit code: 137 */
+  kill(code?: number): void;
+  /** Focus the terminal surface */
+  focus(): void;
+  /**
+   * Resize the terminal; the app restarts at the new size. With
+   * `fit="container"` this stops following the container until the `fit`
+   * or `size` prop changes.
+   */
+  resize(cols: number, rows: number): void;
+  /** Plain text of the visible screen */
+  getText(): string;
+  getStatus(): TuiPreviewStatus;
+  /** Control `cast` recording playback */
+  play(): void;
+  pause(): void;
+  /** Jump to `time` seconds into the recording */
+  seek(time: number): void;
+  setSpeed(speed: number): void;
+  /** Pause / resume / stop the `script` prop playback */
+  pauseScript(): void;
+  resumeScript(): void;
+  stopScript(): void;
+  /** Start recording stdout, input and resizes to asciicast v2 */
+  startRecording(): void;
+  /** Stop recording and return the asciicast v2 document, or null if not recording */
+  stopRecording(): string | null;
+  /** Styled SVG of the visible screen */
+  exportSvg(): string;
+  /** `<pre>` HTML with styled spans of the visible screen */
+  exportHtml(): string;
+  /** ANSI that redraws the visible screen, cursor and modes in a fresh terminal */
+  exportAnsi(): string;
+  /** PNG of the rendered canvas */
+  exportPng(): Promise<Blob>;
+  /**
+   * Snapshot the running guest for the `session` prop, or null if nothing is
+   * running or the app doesn't export `tui_preview_resume`.
+   */
+  saveSession(): TuiSessionSnapshot | null;
+}
 
 export interface GhosttyTheme {
   background: string;
@@ -78,27 +306,68 @@ export interface GhosttyTheme {
   brightWhite: string;
 }
 
+/** Time source backing the WASI clock imports. All values are in nanoseconds. */
+export interface WasiClockSource {
+  /** Wall-clock time since the Unix epoch (CLOCK_REALTIME). */
+  realtime(): bigint;
+  /** Monotonic time from an arbitrary origin (CLOCK_MONOTONIC). */
+  monotonic(): bigint;
+  /** Reported resolution of `realtime`. */
+  realtimeResolution: bigint;
+  /** Reported resolution of `monotonic` and the CPU-time clocks. */
+  monotonicResolution: bigint;
+}
+
 export interface WasiOptions {
   args: string[];
   env: Record<string, string>;
   stdout: (data: Uint8Array) => void;
   stderr: (data: Uint8Array) => void;
   onExit: (code: number) => void;
+  /** Called with each chunk accepted by pushInput() */
+  onInput?: (data: Uint8Array) => void;
+  /**
+   * Clock source for clock_time_get / clock_res_get. Default: performance.now() + Date.now().
+   * Can't be combined with `determinism`.
+   */
+  clock?: WasiClockSource;
+  /** Seeded random_get and a virtual clock that advances only on poll_oneoff; excludes `clock` */
+  determinism?: TuiDeterminismOptions;
 }
 
 export interface ResolvedTuiPreviewOptions {
-  wasm: string | URL;
+  /** App to run; null when playing a `cast` recording */
+  wasm: string | URL | null;
+  cast: string | URL | null;
+  playback: Required<Omit<TuiCastPlaybackOptions, "idleTimeLimit">> & { idleTimeLimit?: number };
   env: Record<string, string>;
+  determinism: Required<TuiDeterminismOptions> | null;
+  stdin: TuiStdinSource | null;
+  closeStdin: boolean;
+  script: Required<TuiScriptOptions> | null;
   interactive: boolean;
+  start: TuiStartMode;
+  poster: TuiPosterSnapshot | null;
+  session: TuiSessionSnapshot | null;
   mode: TuiRenderMode;
   fit: TuiFitMode;
   size: TuiRuntimeSize;
-  terminal: Required<Omit<TuiTerminalOptions, "theme" | "wasmUrl">> & {
+  terminal: Required<
+    Omit<TuiTerminalOptions, "theme" | "wasmUrl" | "fontFamilyBold" | "fontFamilyItalic">
+  > & {
     theme?: Partial<GhosttyTheme>;
     wasmUrl?: string | URL;
+    fontFamilyBold?: string;
+    fontFamilyItalic?: string;
   };
   resolveArgv: (size: TuiRuntimeSize) => string[];
+  stderr: TuiStderrTarget;
+  record: boolean;
+  onRecordingComplete?: (cast: string) => void;
+  onStdout?: TuiOutputCallback;
+  onStderr?: TuiOutputCallback;
   onExit?: (code: number) => void;
   onError?: (error: unknown) => void;
   onStatusChange?: (status: TuiPreviewStatus) => void;
+  onTitleChange?: (title: string) => void;
 }
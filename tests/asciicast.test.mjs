@@ -0,0 +1,103 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { mkdtemp, writeFile } from "node:fs/promises";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { pathToFileURL } from "node:url";
+import { CastPlayer, CastRecorder, loadAsciicast, parseAsciicast } from "../dist/core/index.js";
+
+const CAST = [
+  JSON.stringify({ version: 2, width: 20, height: 5, idle_time_limit: 1 }),
+  JSON.stringify([0.5, "o", "hello"]),
+  JSON.stringify([1.0, "i", "x"]),
+  JSON.stringify([1.5, "r", "30x6"]),
+  JSON.stringify([5.0, "m", "chapter"]),
+  JSON.stringify([5.5, "o", " world"]),
+  "",
+].join("\n");
+
+function recordingTarget() {
+  const calls = [];
+  return {
+    calls,
+    target: {
+      write: (text) => calls.push(["write", text]),
+      resize: (cols, rows) => calls.push(["resize", cols, rows]),
+      reset: () => calls.push(["reset"]),
+    },
+  };
+}
+
+test("parseAsciicast reads the header and events", () => {
+  const cast = parseAsciicast(CAST);
+  assert.equal(cast.header.width, 20);
+  assert.equal(cast.header.height, 5);
+  assert.deepEqual(
+    cast.events.map((event) => event.type),
+    ["o", "i", "r", "m", "o"]
+  );
+  assert.throws(() => parseAsciicast(JSON.stringify({ version: 1, width: 1, height: 1 })));
+});
+
+test("loadAsciicast reads file paths and file: URLs under Node", async () => {
+  const path = join(await mkdtemp(join(tmpdir(), "tui-preview-")), "demo.cast");
+  await writeFile(path, CAST);
+  for (const source of [path, pathToFileURL(path)]) {
+    const cast = await loadAsciicast(source);
+    assert.equal(cast.header.width, 20);
+    assert.equal(cast.events.length, 5);
+  }
+});
+
+test("CastPlayer compresses idle time and replays from the start when seeking back", () => {
+  const { calls, target } = recordingTarget();
+  const player = new CastPlayer(parseAsciicast(CAST), target);
+
+  assert.equal(player.duration, 3);
+  assert.deepEqual(player.markers, [{ time: 2.5, label: "chapter" }]);
+
+  player.seek(2);
+  assert.deepEqual(calls, [
+    ["write", "hello"],
+    ["resize", 30, 6],
+  ]);
+
+  calls.length = 0;
+  player.seek(0.75);
+  assert.deepEqual(calls, [
+    ["reset"],
+    ["resize", 20, 5],
+    ["write", "hello"],
+  ]);
+
+  calls.length = 0;
+  player.seek(10);
+  assert.deepEqual(calls, [
+    ["resize", 30, 6],
+    ["write", " world"],
+  ]);
+  assert.equal(player.currentTime, 3);
+  player.dispose();
+});
+
+test("CastRecorder produces a parseable asciicast with relative timestamps", () => {
+  let now = 1000;
+  const recorder = new CastRecorder({ width: 80, height: 24 }, () => now);
+  recorder.output("$ ");
+  now += 250;
+  recorder.input("ls\r");
+  now += 250;
+  recorder.resize(80, 24);
+  recorder.resize(100, 30);
+  recorder.output("a.txt\r\n");
+
+  const cast = parseAsciicast(recorder.toString());
+  assert.equal(cast.header.width, 80);
+  assert.equal(cast.header.height, 24);
+  assert.deepEqual(cast.events, [
+    { time: 0, type: "o", data: "$ " },
+    { time: 0.25, type: "i", data: "ls\r" },
+    { time: 0.5, type: "r", data: "100x30" },
+    { time: 0.5, type: "o", data: "a.txt\r\n" },
+  ]);
+});
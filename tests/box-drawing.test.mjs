@@ -0,0 +1,84 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { drawBoxGlyph, isBoxGlyph } from "../dist/core/index.js";
+
+/** A context that records fillRect calls as device pixels on a width × height grid. */
+function pixelContext(width, height, dpr) {
+  const pixels = new Uint8Array(width * height);
+  const noop = () => {};
+  const ctx = {
+    fillStyle: "",
+    strokeStyle: "",
+    lineWidth: 1,
+    lineCap: "butt",
+    globalAlpha: 1,
+    save: noop,
+    restore: noop,
+    beginPath: noop,
+    moveTo: noop,
+    lineTo: noop,
+    arcTo: noop,
+    ellipse: noop,
+    closePath: noop,
+    rect: noop,
+    clip: noop,
+    fill: noop,
+    stroke: noop,
+    fillRect(x, y, w, h) {
+      const left = Math.round(x * dpr);
+      const top = Math.round(y * dpr);
+      const right = Math.round((x + w) * dpr);
+      const bottom = Math.round((y + h) * dpr);
+      for (let py = top; py < bottom; py++) {
+        for (let px = left; px < right; px++) pixels[py * width + px] = 1;
+      }
+    },
+  };
+  const row = (y) => [...pixels.subarray(y * width, (y + 1) * width)];
+  const column = (x) => Array.from({ length: height }, (_, y) => pixels[y * width + x]);
+  return { ctx, pixels, row, column };
+}
+
+test("box drawing lines connect across cell boundaries", () => {
+  for (const dpr of [1, 1.5, 2]) {
+    const cellW = 9;
+    const cellH = 17;
+    const width = Math.round(3 * cellW * dpr);
+    const height = Math.round(3 * cellH * dpr);
+    const { ctx, row, column } = pixelContext(width, height, dpr);
+    // A horizontal line across the middle row and a vertical one down the middle column.
+    for (let x = 0; x < 3; x++) drawBoxGlyph(ctx, 0x2500, x * cellW, cellH, cellW, cellH, dpr, "#fff");
+    for (let y = 0; y < 3; y++) {
+      if (y !== 1) drawBoxGlyph(ctx, 0x2502, cellW, y * cellH, cellW, cellH, dpr, "#fff");
+    }
+    drawBoxGlyph(ctx, 0x253c, cellW, cellH, cellW, cellH, dpr, "#fff");
+
+    // The fullest row and column are the lines themselves.
+    const filled = (line) => line.filter(Boolean).length;
+    const fullest = (count, line) =>
+      [...Array(count).keys()].reduce((best, i) => (filled(line(i)) > filled(line(best)) ? i : best));
+    const lineRow = fullest(height, row);
+    const lineColumn = fullest(width, column);
+    assert.ok(row(lineRow).every(Boolean), `horizontal line has gaps at dpr ${dpr}`);
+    assert.ok(column(lineColumn).every(Boolean), `vertical line has gaps at dpr ${dpr}`);
+  }
+});
+
+test("block elements fill their share of the cell exactly", () => {
+  const dpr = 1.25;
+  const { ctx, pixels } = pixelContext(Math.round(10 * dpr), Math.round(40 * dpr), dpr);
+  // ▀ over ▄ in two stacked cells, plus a full block below: no gaps or overlaps.
+  drawBoxGlyph(ctx, 0x2580, 0, 0, 10, 20, dpr, "#fff");
+  drawBoxGlyph(ctx, 0x2584, 0, 0, 10, 20, dpr, "#fff");
+  drawBoxGlyph(ctx, 0x2588, 0, 20, 10, 20, dpr, "#fff");
+  assert.ok(pixels.every(Boolean));
+});
+
+test("other codepoints are left to the font", () => {
+  const { ctx, pixels } = pixelContext(10, 20, 1);
+  assert.equal(isBoxGlyph("A".codePointAt(0)), false);
+  assert.equal(drawBoxGlyph(ctx, "A".codePointAt(0), 0, 0, 10, 20, 1, "#fff"), false);
+  assert.ok(pixels.every((pixel) => pixel === 0));
+  assert.equal(isBoxGlyph(0x2800 + 0xff), true);
+  assert.equal(isBoxGlyph(0xe0b0), true);
+});
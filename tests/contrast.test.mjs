@@ -0,0 +1,32 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { contrastRatio, daltonize, ensureContrast } from "../dist/core/index.js";
+
+const black = { r: 0, g: 0, b: 0 };
+const white = { r: 255, g: 255, b: 255 };
+const darkGray = { r: 0x55, g: 0x55, b: 0x55 };
+
+test("ensureContrast lifts dim text just enough and leaves readable text alone", () => {
+  assert.equal(Math.round(contrastRatio(black, white)), 21);
+  assert.ok(contrastRatio(darkGray, black) < 4.5);
+
+  const lifted = ensureContrast(darkGray, black, 4.5);
+  assert.ok(contrastRatio(lifted, black) >= 4.5);
+  // Lighter on a dark background, and not all the way to white.
+  assert.ok(lifted.r > darkGray.r && lifted.r < 255);
+
+  assert.deepEqual(ensureContrast(white, black, 4.5), white);
+  // Unreachable ratios fall back to the best extreme.
+  assert.deepEqual(ensureContrast(darkGray, { r: 128, g: 128, b: 128 }, 21), black);
+});
+
+test("daltonize separates red and green for protanopia and leaves grays alone", () => {
+  const red = { r: 200, g: 40, b: 40 };
+  const green = { r: 40, g: 160, b: 40 };
+  assert.deepEqual(daltonize(darkGray, "protanopia"), darkGray);
+  assert.deepEqual(daltonize(red, "none"), red);
+  const shifted = daltonize(red, "protanopia");
+  assert.notDeepEqual(shifted, red);
+  // The lost red difference moves into blue, which green doesn't gain as much of.
+  assert.ok(shifted.b - red.b > daltonize(green, "protanopia").b - green.b);
+});
@@ -0,0 +1,48 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { describeStyles, hasJSPI, runTui } from "../dist/core/index.js";
+import { GHOSTTY_WASM, assertSnapshot, skipWithoutGhostty as skip } from "./helpers.mjs";
+
+const COUNTER_WASM = new URL("../example/counter.wasm", import.meta.url);
+const MINESWEEPER_WASM = new URL("../example/minesweeper.wasm", import.meta.url);
+
+const size = { cols: 40, rows: 12 };
+
+test("describeStyles lists non-default runs with end-exclusive columns", () => {
+  const plain = { char: " ", fg: null, bg: null, bold: false, italic: false, underline: false, inverse: false, invisible: false, faint: false, width: 1 };
+  const bold = { ...plain, bold: true, fg: "#ff0000" };
+  assert.equal(describeStyles([[plain, bold, bold, plain], [plain]]), "0:1-3 bold fg=#ff0000");
+});
+
+test("counter increments and decrements", { skip }, async () => {
+  const initial = await runTui(COUNTER_WASM, { size, ghosttyWasm: GHOSTTY_WASM });
+  assertSnapshot("counter-initial", initial);
+  assert.equal(initial.exitCode, null);
+
+  const after = await runTui(COUNTER_WASM, {
+    size,
+    ghosttyWasm: GHOSTTY_WASM,
+    steps: ["press Up Up Up", "press Down"],
+  });
+  assertSnapshot("counter-after-input", after);
+});
+
+test("minesweeper renders a board and reacts to input deterministically", { skip }, async () => {
+  const options = {
+    size: { cols: 80, rows: 24 },
+    ghosttyWasm: GHOSTTY_WASM,
+    steps: ["press Right Down Space"],
+  };
+  const first = await runTui(MINESWEEPER_WASM, options);
+  const second = await runTui(MINESWEEPER_WASM, options);
+  assertSnapshot("minesweeper-reveal", first);
+  assert.equal(first.text, second.text);
+  assert.equal(first.styles, second.styles);
+});
+
+test("runTui without JSPI rejects steps that wait on the guest", { skip: hasJSPI && "JSPI is available" }, async () => {
+  await assert.rejects(
+    runTui(COUNTER_WASM, { size, ghosttyWasm: GHOSTTY_WASM, steps: ["wait 100ms"] }),
+    /needs JSPI .* for wait steps/
+  );
+});
@@ -0,0 +1,62 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import {
+  resolveTheme,
+  viewportRowText,
+  viewportRowToSpans,
+  viewportToHtml,
+  viewportToSpans,
+  viewportToSvg,
+  viewportToText,
+} from "../dist/core/index.js";
+import { viewport } from "./helpers.mjs";
+
+const BOLD = 1 << 0;
+const FG = 1 << 3;
+const BG = 1 << 6;
+
+const screen = viewport(
+  [
+    { char: "<" },
+    { char: "b", fg: [255, 0, 0], flags: BOLD | FG },
+    { char: ">" },
+    { char: " " },
+    { char: " ", bg: [0, 0, 255], flags: BG },
+    { char: "" },
+  ],
+  3
+);
+
+test("viewportToSvg emits one text run per style span and background rects", () => {
+  const svg = viewportToSvg(screen, { fontSize: 10, cellWidth: 6, cellHeight: 12 });
+  assert.match(svg, /^<svg xmlns="http:\/\/www\.w3\.org\/2000\/svg" width="18" height="24"/);
+  assert.match(svg, /<text x="0" y="10" fill="#a9b1d6" textLength="6" lengthAdjust="spacingAndGlyphs">&lt;<\/text>/);
+  assert.match(svg, /<text x="6" y="10" fill="rgb\(255, 0, 0\)" textLength="6"[^>]* font-weight="bold">b<\/text>/);
+  assert.match(svg, /<rect x="6" y="12" width="6" height="12" fill="rgb\(0, 0, 255\)"\/>/);
+  assert.equal(svg.match(/<text /g).length, 3);
+});
+
+test("viewportToHtml keeps column positions and escapes text", () => {
+  const html = viewportToHtml(screen);
+  assert.match(html, /^<pre class="tui-screen" style="[^"]*">/);
+  const body = html.replace(/^<pre[^>]*>/, "").replace(/<\/pre>$/, "");
+  assert.deepEqual(body.split("\n"), [
+    '&lt;<span style="color:rgb(255, 0, 0);font-weight:bold">b</span>&gt;',
+    ' <span style="color:#a9b1d6;background:rgb(0, 0, 255)"> </span>',
+  ]);
+});
+
+test("never-written cells keep their column, wide-character spacers do not", () => {
+  const gaps = viewport(
+    [{ char: "a" }, { width: 0 }, { char: "b" }, { char: "界", width: 2 }, { width: 0 }, { char: "c" }],
+    6
+  );
+  assert.equal(viewportToText(gaps), "a b界c");
+  assert.match(viewportToHtml(gaps), />a b界c<\/pre>$/);
+});
+
+test("row helpers match the whole-screen conversions", () => {
+  const theme = resolveTheme();
+  assert.deepEqual([0, 1].map((y) => viewportRowText(screen, y)), viewportToText(screen).split("\n"));
+  assert.deepEqual([0, 1].map((y) => viewportRowToSpans(screen, y, theme)), viewportToSpans(screen, theme));
+});
@@ -0,0 +1,57 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { createHeadlessTerminal, loadLibGhostty, resolveTheme } from "../dist/core/index.js";
+import { GHOSTTY_WASM, skipWithoutGhostty as skip } from "./helpers.mjs";
+
+test("headless terminal renders text and styles without a DOM", { skip }, async () => {
+  const term = await createHeadlessTerminal({ cols: 20, rows: 3, wasm: GHOSTTY_WASM });
+  try {
+    term.write("\x1b[1;31mhi\x1b[0m there\nnext");
+    assert.equal(term.getText(), "hi there\nnext\n");
+
+    const [first] = term.getCells();
+    assert.equal(first[0].char, "h");
+    assert.equal(first[0].bold, true);
+    assert.notEqual(first[0].fg, null);
+    assert.equal(first[3].bold, false);
+
+    term.write("\x1b[c");
+    assert.ok(term.drainResponses().length > 0);
+
+    term.resize(30, 5);
+    assert.equal(term.cols, 30);
+    assert.equal(term.getCells().length, 5);
+  } finally {
+    term.dispose();
+  }
+});
+
+test("takeDirtyRows only reports rows written since the last call", { skip }, async () => {
+  const runtime = await loadLibGhostty(GHOSTTY_WASM);
+  const term = runtime.createTerminal(10, 4, resolveTheme());
+  try {
+    term.write("top\r\nsecond");
+    assert.deepEqual(term.takeDirtyRows().rows, [0, 1, 2, 3]);
+
+    term.write("\x1b[3;1Hthird");
+    const { viewport, rows } = term.takeDirtyRows();
+    assert.deepEqual(rows, [2]);
+    assert.deepEqual(viewport.buffer, term.getViewportData(false).buffer);
+
+    assert.deepEqual(term.takeDirtyRows().rows, []);
+
+    term.write("\x1b[1;1H\x1b[2K\x1b[4;1Hlast");
+    const update = term.takeDirtyRows();
+    assert.deepEqual(update.rows, [0, 3]);
+    assert.equal(update.viewport, viewport);
+    assert.deepEqual(update.viewport.buffer, term.getViewportData(false).buffer);
+
+    term.resize(12, 3);
+    const resized = term.takeDirtyRows();
+    assert.deepEqual(resized.rows, [0, 1, 2]);
+    assert.equal(resized.viewport.cols, 12);
+    assert.equal(resized.viewport.buffer.length, 12 * 3 * 16);
+  } finally {
+    term.dispose();
+  }
+});
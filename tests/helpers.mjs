@@ -0,0 +1,45 @@
+import assert from "node:assert/strict";
+import { existsSync, mkdirSync, readFileSync, writeFileSync } from "node:fs";
+
+/** libghostty wasm that `npm test` builds into dist before running the tests. */
+export const GHOSTTY_WASM = new URL("../dist/core/ghostty-vt.wasm", import.meta.url);
+
+/**
+ * `skip` for tests that need GHOSTTY_WASM: a bare `node --test` without it
+ * skips them, but CI never does, so a missing build fails there instead.
+ */
+export const skipWithoutGhostty =
+  existsSync(GHOSTTY_WASM) || process.env.CI ? false : "ghostty-vt.wasm not built (npm test builds it)";
+
+/**
+ * Build a ViewportData from cell descriptions; cells without `width` take
+ * one column, `{ width: 0 }` is an empty cell.
+ */
+export function viewport(cells, cols) {
+  const buffer = new Uint8Array(cells.length * 16);
+  const view = new DataView(buffer.buffer);
+  cells.forEach(({ char, fg, bg, flags = 0, width = 1 }, index) => {
+    const base = index * 16;
+    view.setUint32(base, char ? char.codePointAt(0) : 0, true);
+    if (fg) fg.forEach((c, i) => view.setUint8(base + 4 + i, c));
+    if (bg) bg.forEach((c, i) => view.setUint8(base + 7 + i, c));
+    view.setUint8(base + 10, flags);
+    view.setUint8(base + 11, width);
+  });
+  return { cols, rows: cells.length / cols, buffer };
+}
+
+/**
+ * Compare a runTui snapshot with tests/snapshots/<name>.snap. Run with
+ * UPDATE_SNAPSHOTS=1 to write the files instead.
+ */
+export function assertSnapshot(name, { text, styles }) {
+  const actual = `${text}\n--- styles ---\n${styles}\n`;
+  const file = new URL(`./snapshots/${name}.snap`, import.meta.url);
+  if (process.env.UPDATE_SNAPSHOTS) {
+    mkdirSync(new URL("./snapshots/", import.meta.url), { recursive: true });
+    writeFileSync(file, actual);
+    return;
+  }
+  assert.equal(actual, readFileSync(file, "utf8"), `snapshot ${name} changed (UPDATE_SNAPSHOTS=1 rewrites it)`);
+}
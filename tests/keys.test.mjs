@@ -0,0 +1,22 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { encodeKey, encodeKeys } from "../dist/core/index.js";
+
+test("encodeKey maps named keys, aliases and modifiers", () => {
+  assert.equal(encodeKey("Enter"), "\r");
+  assert.equal(encodeKey("Down"), "\x1b[B");
+  assert.equal(encodeKey("arrowleft"), "\x1b[D");
+  assert.equal(encodeKey("Shift+Tab"), "\x1b[Z");
+  assert.equal(encodeKey("Ctrl+C"), "\x03");
+  assert.equal(encodeKey("Alt+x"), "\x1bx");
+  assert.equal(encodeKey("Shift+a"), "A");
+  assert.equal(encodeKey("q"), "q");
+  assert.equal(encodeKey("Space"), " ");
+  assert.equal(encodeKey("Hyper+q"), null);
+  assert.equal(encodeKey("F13"), null);
+});
+
+test("encodeKeys rejects a sequence with an unknown key as a whole", () => {
+  assert.deepEqual(encodeKeys(["a", "Enter", "Ctrl+C"]), ["a", "\r", "\x03"]);
+  assert.throws(() => encodeKeys(["a", "Bogus", "Enter"]), /Unknown key: Bogus/);
+});
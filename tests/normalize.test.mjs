@@ -1,6 +1,6 @@
 import test from "node:test";
 import assert from "node:assert/strict";
-import { resolveTuiPreviewProps } from "../dist/core/index.js";
+import { reduceTermSize, resolveTuiPreviewProps } from "../dist/core/index.js";
 
 test("modern props resolve with container fit defaults", () => {
   const resolved = resolveTuiPreviewProps({
@@ -12,6 +12,7 @@ test("modern props resolve with container fit defaults", () => {
   assert.deepEqual(resolved.size, { cols: 80, rows: 24 });
   assert.equal(resolved.interactive, true);
   assert.equal(resolved.mode, "interactive");
+  assert.equal(resolved.determinism, null);
   assert.equal(resolved.resolveArgv({ cols: 10, rows: 4 }).length, 0);
 });
 
@@ -38,3 +39,20 @@ test("static mode is non-interactive and keeps modern terminal options", () => {
   assert.equal(resolved.interactive, false);
   assert.deepEqual(resolved.resolveArgv({ cols: 5, rows: 2 }), ["5", "2"]);
 });
+
+test("an imperative resize stops container fitting until the props reset it", () => {
+  const size = { cols: 80, rows: 24 };
+  let state = reduceTermSize({ size: null, fitting: false }, { type: "reset", fit: "container", size });
+  assert.deepEqual(state, { size: null, fitting: true });
+
+  state = reduceTermSize(state, { type: "fit", size: { cols: 100, rows: 30 } });
+  assert.deepEqual(state.size, { cols: 100, rows: 30 });
+  assert.equal(reduceTermSize(state, { type: "fit", size: { cols: 100, rows: 30 } }), state);
+
+  state = reduceTermSize(state, { type: "resize", size: { cols: 40, rows: 0 } });
+  assert.deepEqual(state, { size: { cols: 40, rows: 1 }, fitting: false });
+  assert.equal(reduceTermSize(state, { type: "fit", size: { cols: 120, rows: 40 } }), state);
+
+  assert.deepEqual(reduceTermSize(state, { type: "reset", fit: "container", size }), { size: null, fitting: true });
+  assert.deepEqual(reduceTermSize(state, { type: "reset", fit: "none", size }), { size, fitting: false });
+});
@@ -0,0 +1,75 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import {
+  ansiToSpans,
+  capturePoster,
+  decodeCellBuffer,
+  encodeCellBuffer,
+  posterToSpans,
+  resolveTheme,
+  viewportToText,
+} from "../dist/core/index.js";
+import { GHOSTTY_WASM, skipWithoutGhostty } from "./helpers.mjs";
+
+const COUNTER_WASM = new URL("../example/counter.wasm", import.meta.url);
+
+const theme = resolveTheme();
+
+function cell(view, index, codepoint, { fg, bg, flags = 0 } = {}) {
+  const base = index * 16;
+  view.setUint32(base, codepoint, true);
+  if (fg) fg.forEach((c, i) => view.setUint8(base + 4 + i, c));
+  if (bg) bg.forEach((c, i) => view.setUint8(base + 7 + i, c));
+  view.setUint8(base + 10, flags);
+  view.setUint8(base + 11, 1);
+}
+
+test("ansiToSpans applies SGR styles and drops other escapes", () => {
+  const lines = ansiToSpans("\x1b[2J\x1b[1;31mError\x1b[0m: bad\r\n\x1b[48;5;21m \x1b[49m", theme);
+  assert.equal(lines.length, 2);
+  assert.deepEqual(
+    lines[0].map((span) => [span.text, span.fg, span.bold]),
+    [
+      ["Error", theme.red, true],
+      [": bad", theme.foreground, false],
+    ]
+  );
+  assert.deepEqual(
+    lines[1].map((span) => [span.text, span.bg]),
+    [[" ", "rgb(0, 0, 255)"]]
+  );
+});
+
+test("ansiToSpans clips to the poster size", () => {
+  const lines = ansiToSpans("abcdef\nghi\njkl", theme, { cols: 4, rows: 2 });
+  assert.deepEqual(
+    lines.map((line) => line.map((span) => span.text).join("")),
+    ["abcd", "ghi"]
+  );
+});
+
+test("cell buffer posters round-trip through base64 and resolve inverse colors", () => {
+  const cols = 3;
+  const rows = 1;
+  const buffer = new Uint8Array(cols * rows * 16);
+  const view = new DataView(buffer.buffer);
+  cell(view, 0, "h".codePointAt(0));
+  cell(view, 1, "i".codePointAt(0), { fg: [255, 0, 0], flags: (1 << 3) | (1 << 4) });
+  cell(view, 2, 0);
+
+  const cells = encodeCellBuffer(buffer);
+  assert.deepEqual(decodeCellBuffer(cells), buffer);
+
+  const [line] = posterToSpans({ cols, rows, cells }, theme);
+  assert.deepEqual(line, [
+    { text: "h", col: 0, width: 1, fg: theme.foreground, bg: null, bold: false, italic: false, underline: false, faint: false },
+    { text: "i", col: 1, width: 1, fg: theme.background, bg: "rgb(255, 0, 0)", bold: false, italic: false, underline: false, faint: false },
+  ]);
+});
+
+test("capturePoster returns once an interactive guest reads EOF", { skip: skipWithoutGhostty }, async () => {
+  const poster = await capturePoster(COUNTER_WASM, { cols: 40, rows: 12, stdin: "k", ghosttyWasm: GHOSTTY_WASM });
+  const text = viewportToText({ cols: poster.cols, rows: poster.rows, buffer: decodeCellBuffer(poster.cells) });
+  assert.match(text, /│\s+1\s+│/);
+  assert.doesNotMatch(text, /stdin closed/);
+});
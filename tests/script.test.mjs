@@ -0,0 +1,85 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { ScriptPlayer, normalizeScriptStep, parseScriptStep } from "../dist/core/index.js";
+
+test("parseScriptStep understands the textual step forms", () => {
+  assert.deepEqual(parseScriptStep('type "hi\\n"', 10), { kind: "type", text: "hi\n", delay: 10 });
+  assert.deepEqual(parseScriptStep("press Down Enter"), { kind: "press", keys: ["Down", "Enter"] });
+  assert.deepEqual(parseScriptStep("wait 500ms"), { kind: "wait", ms: 500 });
+  assert.deepEqual(parseScriptStep("wait 2s"), { kind: "wait", ms: 2000 });
+  assert.deepEqual(parseScriptStep('waitForText "Game Over" 3s'), {
+    kind: "waitForText",
+    match: "Game Over",
+    timeout: 3000,
+  });
+  assert.throws(() => parseScriptStep("jump 3"));
+});
+
+test("ScriptPlayer types, presses and waits for screen text", async () => {
+  const sent = [];
+  let screen = "";
+  const player = new ScriptPlayer(
+    [
+      { type: "ab", delay: 0 },
+      { press: "Enter" },
+      { waitForText: "ready", timeout: 1000 },
+      "press Ctrl+C",
+    ].map((step) => normalizeScriptStep(step)),
+    {
+      sendInput: (data) => sent.push(data),
+      getText: () => screen,
+    }
+  );
+
+  const done = player.play();
+  setTimeout(() => {
+    screen = "app ready";
+    player.notifyOutput();
+  }, 5);
+
+  assert.equal(await done, "done");
+  assert.deepEqual(sent, ["a", "b", "\r", "\x03"]);
+});
+
+test("ScriptPlayer stops cleanly while waiting", async () => {
+  const player = new ScriptPlayer([normalizeScriptStep({ wait: 60_000 })], {
+    sendInput: () => {},
+    getText: () => "",
+  });
+  const done = player.play();
+  player.stop();
+  assert.equal(await done, "stopped");
+});
+
+test("ScriptPlayer holds sleeps and waitForText deadlines while paused", async () => {
+  const sent = [];
+  let screen = "";
+  const player = new ScriptPlayer(
+    [{ wait: 40 }, { press: "Enter" }, { waitForText: "ready", timeout: 40 }, { press: "q" }].map((step) =>
+      normalizeScriptStep(step)
+    ),
+    {
+      sendInput: (data) => sent.push(data),
+      getText: () => screen,
+    }
+  );
+  const pauseFor = async (ms) => {
+    player.pause();
+    await new Promise((resolve) => setTimeout(resolve, ms));
+    player.resume();
+  };
+
+  const done = player.play();
+  await new Promise((resolve) => setTimeout(resolve, 10));
+  await pauseFor(80);
+  await new Promise((resolve) => setTimeout(resolve, 10));
+  assert.deepEqual(sent, [], "the wait step kept counting while paused");
+
+  while (sent.length === 0) await new Promise((resolve) => setTimeout(resolve, 5));
+  await pauseFor(80);
+  screen = "ready";
+  player.notifyOutput();
+
+  assert.equal(await done, "done");
+  assert.deepEqual(sent, ["\r", "q"]);
+});
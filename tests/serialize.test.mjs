@@ -0,0 +1,105 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { createHeadlessTerminal, serializeScreen } from "../dist/core/index.js";
+import { GHOSTTY_WASM, skipWithoutGhostty as skip, viewport } from "./helpers.mjs";
+
+const BOLD = 1 << 0;
+const FG = 1 << 3;
+const BG = 1 << 6;
+const EMPTY = { width: 0 };
+
+test("serializeScreen emits cells with SGR diffs, erased runs and skipped gaps", () => {
+  const screen = viewport(
+    [
+      { char: "a", fg: [255, 0, 0], flags: BOLD | FG },
+      { char: "b", fg: [255, 0, 0], flags: BOLD | FG },
+      EMPTY,
+      { char: "c" },
+      EMPTY,
+      { bg: [0, 0, 255], flags: BG },
+      { bg: [0, 0, 255], flags: BG },
+      EMPTY,
+      EMPTY,
+      EMPTY,
+    ],
+    5
+  );
+  assert.equal(
+    serializeScreen(screen),
+    "\x1bc" +
+      "\x1b[1;1H\x1b[1;38;2;255;0;0mab\x1b[4G\x1b[0mc" +
+      "\x1b[2;1H\x1b[48;2;0;0;255m\x1b[2X" +
+      "\x1b[49m\x1b[H"
+  );
+});
+
+test("serializeScreen restores saved cursor, margins, modes, cursor and pen", () => {
+  const screen = viewport([{ char: "x" }, EMPTY, EMPTY, EMPTY, EMPTY, EMPTY], 2);
+  const ansi = serializeScreen(screen, {
+    cursorX: 1,
+    cursorY: 2,
+    savedX: 1,
+    savedY: 0,
+    scrollTop: 1,
+    scrollBottom: 2,
+    scrollLeft: 0,
+    scrollRight: 1,
+    wraparound: false,
+    pendingWrap: false,
+    originMode: true,
+    leftRightMargin: false,
+    cursorVisible: true,
+    altScreen: false,
+    applicationCursorKeys: false,
+    bracketedPaste: false,
+    styleFlags: BOLD,
+    fg: null,
+    bg: { r: 1, g: 2, b: 3 },
+  });
+  assert.equal(
+    ansi,
+    "\x1bc\x1b[1;1Hx" + "\x1b[1;2H\x1b7" + "\x1b[2;3r" + "\x1b[?7l\x1b[?6h" + "\x1b[2;2H" + "\x1b[1;48;2;1;2;3m"
+  );
+});
+
+test("serializeScreen enters the alternate screen before drawing and restores input modes", () => {
+  const screen = viewport([{ char: "x" }, EMPTY], 2);
+  const ansi = serializeScreen(screen, {
+    cursorX: 1,
+    cursorY: 0,
+    savedX: 0,
+    savedY: 0,
+    scrollTop: 0,
+    scrollBottom: 0,
+    scrollLeft: 0,
+    scrollRight: 1,
+    wraparound: true,
+    pendingWrap: false,
+    originMode: false,
+    leftRightMargin: false,
+    cursorVisible: false,
+    altScreen: true,
+    applicationCursorKeys: true,
+    bracketedPaste: true,
+    styleFlags: 0,
+    fg: null,
+    bg: null,
+  });
+  assert.equal(ansi, "\x1bc\x1b[?1049h\x1b[1;1Hx" + "\x1b[?1h\x1b[?2004h\x1b[?25l" + "\x1b[1;2H");
+});
+
+test("serialized screens replay into an identical terminal", { skip }, async () => {
+  const source = await createHeadlessTerminal({ cols: 12, rows: 4, wasm: GHOSTTY_WASM });
+  const target = await createHeadlessTerminal({ cols: 12, rows: 4, wasm: GHOSTTY_WASM });
+  try {
+    source.write("\x1b[1;31mred\x1b[0m plain\n\x1b[7minv\x1b[0m  \x1b[44m\x1b[3X\x1b[0m界!");
+    source.write("\x1b[4;10Hend\x1b[2;4r\x1b[3;2H\x1b[4m");
+    source.write("\x1b[?1049h\x1b[?1h\x1b[?2004h\x1b[?25l");
+    target.write(source.serialize());
+    assert.deepEqual(target.getCells(), source.getCells());
+    assert.deepEqual(target.getState(), source.getState());
+  } finally {
+    source.dispose();
+    target.dispose();
+  }
+});
@@ -0,0 +1,152 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { WasiBridge, decodeSession, encodeSession, instantiateApp, restoreSession } from "../dist/core/index.js";
+
+const MINESWEEPER_WASM = new URL("../example/minesweeper.wasm", import.meta.url);
+/** Reply to the guest's startup size query (cursor position report), as the terminal would. */
+const SIZE_REPLY = "\x1b[20;60R";
+
+function createBridge(output = []) {
+  const decoder = new TextDecoder();
+  return new WasiBridge({
+    args: ["minesweeper"],
+    env: { COLUMNS: "60", LINES: "20" },
+    stdout: (data) => output.push(decoder.decode(data, { stream: true })),
+    stderr: () => {},
+    onExit: () => {},
+    determinism: { seed: 7, epochMs: 0 },
+  });
+}
+
+/** Queue keys one read at a time, then EOF so the guest's loop returns without JSPI. */
+function feedKeys(bridge, keys) {
+  for (const key of keys) bridge.pushInput(key);
+  bridge.closeInput();
+}
+
+function lastFlagCount(output) {
+  const text = output.join("").replace(/\x1b\[[0-9;?]*[A-Za-z]/g, "");
+  const matches = [...text.matchAll(/Flags:\s+(\d+)\//g)];
+  return matches.length > 0 ? Number(matches[matches.length - 1][1]) : null;
+}
+
+test("saveState/restoreState carries stdin and the seeded PRNG into a new bridge", () => {
+  const source = createBridge();
+  const sourceMemory = new WebAssembly.Memory({ initial: 1 });
+  source.attachMemory(sourceMemory);
+  source.imports.random_get(0, 8);
+  source.pushInput("queued");
+  source.pushResponse("\x1b[24;80R");
+
+  const state = source.saveState();
+  assert.deepEqual(
+    state.input.map(({ data, response }) => [new TextDecoder().decode(data), response]),
+    [
+      ["queued", false],
+      ["\x1b[24;80R", true],
+    ]
+  );
+  assert.equal(state.inputClosed, false);
+  assert.equal(state.virtualTimeNs, 0n);
+
+  const target = createBridge();
+  const targetMemory = new WebAssembly.Memory({ initial: 1 });
+  target.attachMemory(targetMemory);
+  target.restoreState(state);
+  source.imports.random_get(0, 16);
+  target.imports.random_get(0, 16);
+  assert.deepEqual(new Uint8Array(targetMemory.buffer, 0, 16), new Uint8Array(sourceMemory.buffer, 0, 16));
+  assert.deepEqual(target.saveState().input, state.input);
+
+  // The restored reply still skips ahead of paused user input.
+  const view = new DataView(targetMemory.buffer);
+  view.setUint32(64, 128, true);
+  view.setUint32(68, 16, true);
+  target.pauseInput();
+  assert.equal(target.imports.fd_read(0, 64, 1, 48), 0);
+  const nread = view.getUint32(48, true);
+  assert.equal(new TextDecoder().decode(new Uint8Array(targetMemory.buffer, 128, nread)), "\x1b[24;80R");
+  assert.equal(target.imports.fd_read(0, 64, 1, 48), 6);
+});
+
+test("a restored minesweeper session continues from the saved board", async () => {
+  const firstOutput = [];
+  const first = createBridge(firstOutput);
+  feedKeys(first, [SIZE_REPLY, "f"]);
+  const firstApp = await instantiateApp(MINESWEEPER_WASM, first);
+  assert.equal(firstApp.resumable, true);
+  await firstApp.run();
+  assert.equal(lastFlagCount(firstOutput), 1);
+
+  const saved = decodeSession(
+    encodeSession({
+      version: 1,
+      wasm: MINESWEEPER_WASM.toString(),
+      cols: 60,
+      rows: 20,
+      guest: firstApp.snapshot(),
+      bridge: { ...first.saveState(), inputClosed: false },
+      terminal: "",
+    })
+  );
+
+  const restoredOutput = [];
+  const restored = createBridge(restoredOutput);
+  const terminalCalls = [];
+  const restoredApp = await restoreSession(
+    saved,
+    MINESWEEPER_WASM,
+    restored,
+    {
+      resize: (cols, rows) => terminalCalls.push(["resize", cols, rows]),
+      write: (data) => terminalCalls.push(["write", data]),
+    },
+    { cols: 70, rows: 22 }
+  );
+  assert.deepEqual(terminalCalls, [
+    ["resize", 70, 22],
+    ["write", ""],
+  ]);
+  feedKeys(restored, ["l", "f"]);
+  await restoredApp.run();
+  assert.equal(lastFlagCount(restoredOutput), 2);
+
+  const freshOutput = [];
+  const fresh = createBridge(freshOutput);
+  feedKeys(fresh, [SIZE_REPLY, "l", "f"]);
+  await (await instantiateApp(MINESWEEPER_WASM, fresh)).run();
+  assert.equal(lastFlagCount(freshOutput), 1);
+});
+
+test("restoring needs the resume export", async () => {
+  const counter = new URL("../example/counter.wasm", import.meta.url);
+  const bridge = createBridge();
+  const app = await instantiateApp(counter, bridge);
+  assert.equal(app.resumable, false);
+  await assert.rejects(
+    instantiateApp(counter, createBridge(), { restore: app.snapshot() }),
+    /no tui_preview_resume export/
+  );
+});
+
+test("encodeSession round-trips bytes and bigints", () => {
+  const session = {
+    version: 1,
+    wasm: "app.wasm",
+    cols: 2,
+    rows: 1,
+    guest: { memorySize: 65536, memory: new Uint8Array([0, 1, 255]), globals: { g: 5n, h: 1.5 } },
+    bridge: {
+      input: [
+        { data: new Uint8Array([104, 105]), response: false },
+        { data: new Uint8Array([27, 91, 48, 110]), response: true },
+      ],
+      inputClosed: true,
+      virtualTimeNs: 42n,
+      randomState: 9,
+    },
+    terminal: "\x1bc\x1b[1;1Hhi",
+  };
+  assert.deepEqual(decodeSession(encodeSession(session)), session);
+  assert.throws(() => decodeSession("{}"), /Not a saved tui-preview session/);
+});
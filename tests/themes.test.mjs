@@ -0,0 +1,80 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import {
+  THEME_PRESETS,
+  parseBase16Yaml,
+  parseGhosttyTheme,
+  parseItermColors,
+  parseWindowsTerminalScheme,
+} from "../dist/core/index.js";
+
+const ANSI_KEYS = [
+  "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+  "brightBlack", "brightRed", "brightGreen", "brightYellow", "brightBlue", "brightMagenta", "brightCyan", "brightWhite",
+];
+
+test("presets define every theme color", () => {
+  for (const [name, theme] of Object.entries(THEME_PRESETS)) {
+    for (const key of ["background", "foreground", "cursor", "selectionBackground", "selectionForeground", ...ANSI_KEYS]) {
+      assert.match(theme[key], /^#[0-9a-f]{6}$/, `${name}.${key}`);
+    }
+  }
+});
+
+test("Ghostty theme files", () => {
+  const theme = parseGhosttyTheme(
+    ["# comment", "palette = 0=#000000", "palette = 9=ff0000", "background = 101010", "foreground = #eee", "cursor-color = #00ff00"].join("\n")
+  );
+  assert.equal(theme.background, "#101010");
+  assert.equal(theme.foreground, "#eeeeee");
+  assert.equal(theme.cursor, "#00ff00");
+  assert.equal(theme.selectionForeground, "#eeeeee");
+  assert.equal(theme.black, "#000000");
+  assert.equal(theme.brightRed, "#ff0000");
+  assert.throws(() => parseGhosttyTheme("palette = 0=#000000"), /no background or foreground/);
+});
+
+test("iTerm2 .itermcolors", () => {
+  const color = (key, r, g, b) => `
+    <key>${key}</key>
+    <dict>
+      <key>Blue Component</key><real>${b}</real>
+      <key>Green Component</key><real>${g}</real>
+      <key>Red Component</key><real>${r}</real>
+    </dict>`;
+  const xml = `<?xml version="1.0"?><plist version="1.0"><dict>
+    ${color("Ansi 1 Color", 1, 0, 0)}
+    ${color("Background Color", 0, 0, 0)}
+    ${color("Foreground Color", 1, 1, 1)}
+    ${color("Selection Color", 0.2, 0.4, 0.6)}
+  </dict></plist>`;
+  const theme = parseItermColors(xml);
+  assert.equal(theme.red, "#ff0000");
+  assert.equal(theme.background, "#000000");
+  assert.equal(theme.foreground, "#ffffff");
+  assert.equal(theme.selectionBackground, "#336699");
+});
+
+test("Windows Terminal schemes", () => {
+  const scheme = { name: "Test", background: "#0C0C0C", foreground: "#CCCCCC", purple: "#881798", brightPurple: "#B4009E" };
+  const theme = parseWindowsTerminalScheme(JSON.stringify(scheme));
+  assert.equal(theme.background, "#0c0c0c");
+  assert.equal(theme.magenta, "#881798");
+  assert.equal(theme.brightMagenta, "#b4009e");
+
+  const settings = { schemes: [{ ...scheme, name: "Other", background: "#ffffff" }, scheme] };
+  assert.equal(parseWindowsTerminalScheme(settings, "Test").background, "#0c0c0c");
+  assert.throws(() => parseWindowsTerminalScheme(settings, "Missing"), /no scheme named "Missing"/);
+});
+
+test("base16 YAML", () => {
+  const lines = Array.from({ length: 16 }, (_, i) => `  base0${i.toString(16).toUpperCase()}: "${i.toString(16).repeat(6)}"`);
+  const theme = parseBase16Yaml(["system: base16", "palette:", ...lines].join("\n"));
+  assert.equal(theme.background, "#000000");
+  assert.equal(theme.foreground, "#555555");
+  assert.equal(theme.selectionBackground, "#222222");
+  assert.equal(theme.red, "#888888");
+  assert.equal(theme.brightBlack, "#333333");
+  assert.equal(theme.brightWhite, "#777777");
+  assert.throws(() => parseBase16Yaml(lines.slice(1).join("\n")), /missing base00/);
+});
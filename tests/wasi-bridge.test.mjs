@@ -1,9 +1,10 @@
 import test from "node:test";
 import assert from "node:assert/strict";
-import { WasiBridge } from "../dist/core/index.js";
+import { CastRecorder, WasiBridge, feedStdin, parseAsciicast } from "../dist/core/index.js";
 
 const WASI_ESUCCESS = 0;
 const WASI_EAGAIN = 6;
+const WASI_EINVAL = 28;
 
 function createBridge() {
   const bridge = new WasiBridge({
@@ -77,3 +78,277 @@ test("poll_oneoff only signals fd_read subscriptions when input is queued", asyn
   const clockEvents = view.getUint32(neventsPtr, true);
   assert.ok(clockEvents === 0 || clockEvents === 1);
 });
+
+test("clock_time_get routes clock ids through the injected clock source", () => {
+  let now = 5_000n;
+  const bridge = new WasiBridge({
+    args: [],
+    env: {},
+    stdout: () => {},
+    stderr: () => {},
+    onExit: () => {},
+    clock: {
+      realtime: () => 1_700_000_000_000_000_000n,
+      monotonic: () => now,
+      realtimeResolution: 1_000_000n,
+      monotonicResolution: 1_000n,
+    },
+  });
+  const memory = new WebAssembly.Memory({ initial: 1 });
+  bridge.attachMemory(memory);
+  const view = new DataView(memory.buffer);
+  const wasi = bridge.imports;
+  const timePtr = 64;
+
+  assert.equal(wasi.clock_time_get(0, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 1_700_000_000_000_000_000n);
+
+  now = 7_500n;
+  assert.equal(wasi.clock_time_get(1, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 7_500n);
+
+  assert.equal(wasi.clock_time_get(2, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 2_500n);
+
+  assert.equal(wasi.clock_time_get(9, 0n, timePtr), WASI_EINVAL);
+
+  assert.equal(wasi.clock_res_get(0, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 1_000_000n);
+  assert.equal(wasi.clock_res_get(1, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 1_000n);
+  assert.equal(wasi.clock_res_get(9, timePtr), WASI_EINVAL);
+});
+
+test("determinism seeds random_get and advances virtual time only on poll", async () => {
+  const run = () => {
+    const bridge = new WasiBridge({
+      args: [],
+      env: {},
+      stdout: () => {},
+      stderr: () => {},
+      onExit: () => {},
+      determinism: { seed: 42, epochMs: 1_000 },
+    });
+    const memory = new WebAssembly.Memory({ initial: 1 });
+    bridge.attachMemory(memory);
+    return { bridge, view: new DataView(memory.buffer), u8: new Uint8Array(memory.buffer) };
+  };
+
+  const first = run();
+  const second = run();
+  assert.equal(first.bridge.imports.random_get(64, 16), WASI_ESUCCESS);
+  assert.equal(second.bridge.imports.random_get(64, 16), WASI_ESUCCESS);
+  assert.deepEqual(first.u8.slice(64, 80), second.u8.slice(64, 80));
+
+  const { bridge, view } = first;
+  const wasi = bridge.imports;
+  const timePtr = 32;
+  assert.equal(wasi.clock_time_get(0, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 1_000_000_000n);
+  assert.equal(wasi.clock_time_get(1, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 0n);
+
+  const inPtr = 256;
+  view.setBigUint64(inPtr, 1n, true);
+  view.setUint8(inPtr + 8, 0); // clock
+  view.setBigUint64(inPtr + 24, 16_000_000n, true);
+  await wasi.poll_oneoff(inPtr, 512, 1, 200);
+
+  // The timeout elapsed in virtual time, so the guest sees it fire.
+  assert.equal(view.getUint32(200, true), 1);
+  assert.equal(view.getBigUint64(512, true), 1n);
+  assert.equal(view.getUint8(512 + 10), 0);
+  assert.equal(wasi.clock_time_get(1, 0n, timePtr), WASI_ESUCCESS);
+  assert.equal(view.getBigUint64(timePtr, true), 16_000_000n);
+
+  assert.throws(
+    () =>
+      new WasiBridge({
+        args: [],
+        env: {},
+        stdout: () => {},
+        stderr: () => {},
+        onExit: () => {},
+        clock: { realtime: () => 0n, monotonic: () => 0n, realtimeResolution: 1n, monotonicResolution: 1n },
+        determinism: { seed: 1, epochMs: 0 },
+      }),
+    /can't be combined with determinism/
+  );
+});
+
+test("closeInput drains queued stdin and then reports EOF", async () => {
+  const { bridge, view, u8 } = createBridge();
+  const wasi = bridge.imports;
+
+  bridge.pushInput("ab");
+  bridge.closeInput();
+  bridge.pushInput("ignored");
+  bridge.pushResponse("\x1b[0n");
+
+  const iovsPtr = 64;
+  const bufPtr = 128;
+  const nreadPtr = 48;
+  view.setUint32(iovsPtr, bufPtr, true);
+  view.setUint32(iovsPtr + 4, 16, true);
+  const read = () => {
+    assert.equal(wasi.fd_read(0, iovsPtr, 1, nreadPtr), WASI_ESUCCESS);
+    return new TextDecoder().decode(u8.slice(bufPtr, bufPtr + view.getUint32(nreadPtr, true)));
+  };
+
+  assert.equal(read(), "\x1b[0n");
+  assert.equal(read(), "ab");
+  assert.equal(bridge.hasReadEof, false);
+  assert.equal(read(), "");
+  assert.equal(bridge.hasReadEof, true);
+
+  const inPtr = 256;
+  const outPtr = 512;
+  view.setBigUint64(inPtr, 7n, true);
+  view.setUint8(inPtr + 8, 1); // fd_read
+  assert.equal(await wasi.poll_oneoff(inPtr, outPtr, 1, 200), WASI_ESUCCESS);
+  assert.equal(view.getUint32(200, true), 1);
+  assert.equal(view.getUint16(outPtr + 24, true), 1); // hangup
+});
+
+test("pauseInput holds stdin back until resumeInput", () => {
+  const { bridge, view, u8 } = createBridge();
+  const wasi = bridge.imports;
+
+  const iovsPtr = 64;
+  const bufPtr = 128;
+  const nreadPtr = 48;
+  view.setUint32(iovsPtr, bufPtr, true);
+  view.setUint32(iovsPtr + 4, 16, true);
+
+  bridge.pauseInput();
+  bridge.pushInput("q");
+  assert.equal(wasi.fd_read(0, iovsPtr, 1, nreadPtr), WASI_EAGAIN);
+
+  bridge.resumeInput();
+  assert.equal(wasi.fd_read(0, iovsPtr, 1, nreadPtr), WASI_ESUCCESS);
+  assert.equal(new TextDecoder().decode(u8.slice(bufPtr, bufPtr + 1)), "q");
+});
+
+test("pauseInput still delivers terminal replies ahead of held-back keys", () => {
+  const { bridge, view, u8 } = createBridge();
+  const wasi = bridge.imports;
+
+  const iovsPtr = 64;
+  const bufPtr = 128;
+  const nreadPtr = 48;
+  view.setUint32(iovsPtr, bufPtr, true);
+  view.setUint32(iovsPtr + 4, 16, true);
+  const read = () => {
+    assert.equal(wasi.fd_read(0, iovsPtr, 1, nreadPtr), WASI_ESUCCESS);
+    return new TextDecoder().decode(u8.slice(bufPtr, bufPtr + view.getUint32(nreadPtr, true)));
+  };
+
+  bridge.pauseInput();
+  bridge.pushInput("q");
+  bridge.pushResponse("\x1b[5;10R");
+  assert.equal(read(), "\x1b[5;10R");
+  assert.equal(wasi.fd_read(0, iovsPtr, 1, nreadPtr), WASI_EAGAIN);
+
+  bridge.resumeInput();
+  assert.equal(read(), "q");
+});
+
+test("recorded sessions keep terminal replies out of input events", () => {
+  const recorder = new CastRecorder({ width: 80, height: 24 }, () => 0);
+  const decoder = new TextDecoder();
+  const bridge = new WasiBridge({
+    args: [],
+    env: {},
+    stdout: (data) => recorder.output(decoder.decode(data)),
+    stderr: () => {},
+    onExit: () => {},
+    onInput: (data) => recorder.input(decoder.decode(data)),
+  });
+  const memory = new WebAssembly.Memory({ initial: 1 });
+  bridge.attachMemory(memory);
+  const view = new DataView(memory.buffer);
+  const query = new TextEncoder().encode("\x1b[6n");
+  new Uint8Array(memory.buffer).set(query, 128);
+  view.setUint32(64, 128, true);
+  view.setUint32(68, query.length, true);
+
+  assert.equal(bridge.imports.fd_write(1, 64, 1, 48), WASI_ESUCCESS);
+  bridge.pushResponse("\x1b[1;1R");
+  bridge.pushInput("q");
+
+  const events = parseAsciicast(recorder.toString()).events;
+  assert.deepEqual(
+    events.filter((event) => event.type === "i").map((event) => event.data),
+    ["q"]
+  );
+  assert.deepEqual(
+    events.filter((event) => event.type === "o").map((event) => event.data),
+    ["\x1b[6n"]
+  );
+});
+
+test("feedStdin pipes a ReadableStream and closes input", async () => {
+  const { bridge, view, u8 } = createBridge();
+  const stream = new ReadableStream({
+    start(controller) {
+      controller.enqueue(new TextEncoder().encode("line 1\n"));
+      controller.enqueue("line 2\n");
+      controller.close();
+    },
+  });
+
+  await feedStdin(bridge, stream, { close: true });
+  assert.equal(bridge.isInputClosed, true);
+
+  const iovsPtr = 64;
+  const bufPtr = 128;
+  const nreadPtr = 48;
+  view.setUint32(iovsPtr, bufPtr, true);
+  view.setUint32(iovsPtr + 4, 64, true);
+  let text = "";
+  for (;;) {
+    assert.equal(bridge.imports.fd_read(0, iovsPtr, 1, nreadPtr), WASI_ESUCCESS);
+    const nread = view.getUint32(nreadPtr, true);
+    if (nread === 0) break;
+    text += new TextDecoder().decode(u8.slice(bufPtr, bufPtr + nread));
+  }
+  assert.equal(text, "line 1\nline 2\n");
+});
+
+test("stdoutStream receives fd_write output and closes on exit", async () => {
+  const { bridge, view, u8 } = createBridge();
+  const wasi = bridge.imports;
+  const reader = bridge.stdoutStream().getReader();
+
+  const bufPtr = 128;
+  const iovsPtr = 64;
+  u8.set(new TextEncoder().encode("hi"), bufPtr);
+  view.setUint32(iovsPtr, bufPtr, true);
+  view.setUint32(iovsPtr + 4, 2, true);
+  assert.equal(wasi.fd_write(1, iovsPtr, 1, 48), WASI_ESUCCESS);
+  assert.equal(wasi.fd_write(2, iovsPtr, 1, 48), WASI_ESUCCESS);
+
+  const first = await reader.read();
+  assert.equal(new TextDecoder().decode(first.value), "hi");
+
+  assert.throws(() => wasi.proc_exit(0));
+  const end = await reader.read();
+  assert.equal(end.done, true);
+});
+
+test("kill after the guest exits does not report a second exit", () => {
+  const exits = [];
+  const bridge = new WasiBridge({
+    args: [],
+    env: {},
+    stdout: () => {},
+    stderr: () => {},
+    onExit: (code) => exits.push(code),
+  });
+  bridge.attachMemory(new WebAssembly.Memory({ initial: 1 }));
+
+  assert.throws(() => bridge.imports.proc_exit(0));
+  bridge.kill();
+  assert.deepEqual(exits, [0]);
+  assert.equal(bridge.isKilled, false);
+});
@@ -23,6 +23,7 @@ fn wasmLogFn(
 
 const cell_bytes = 16;
 const config_bytes = 80;
+const state_bytes = 44;
 const default_tab_width = 8;
 
 const flag_bold: u8 = 1 << 0;
@@ -61,9 +62,44 @@ const EncodedCell = extern struct {
     reserved: [4]u8 = .{ 0, 0, 0, 0 },
 };
 
+const state_flag_wraparound: u32 = 1 << 0;
+const state_flag_pending_wrap: u32 = 1 << 1;
+const state_flag_origin: u32 = 1 << 2;
+const state_flag_left_right_margin: u32 = 1 << 3;
+const state_flag_cursor_visible: u32 = 1 << 4;
+const state_flag_alt_screen: u32 = 1 << 5;
+const state_flag_cursor_keys: u32 = 1 << 6;
+const state_flag_bracketed_paste: u32 = 1 << 7;
+
+const style_has_fg: u8 = 1 << 0;
+const style_has_bg: u8 = 1 << 1;
+
+/// Cursor, margins, modes and the current SGR style, for re-encoding the
+/// screen as ANSI. Style flags use the same bits as EncodedCell.flags.
+const EncodedState = extern struct {
+    cursor_x: u32 = 0,
+    cursor_y: u32 = 0,
+    saved_x: u32 = 0,
+    saved_y: u32 = 0,
+    scroll_top: u32 = 0,
+    scroll_bottom: u32 = 0,
+    scroll_left: u32 = 0,
+    scroll_right: u32 = 0,
+    flags: u32 = 0,
+    style_flags: u8 = 0,
+    fg_r: u8 = 0,
+    fg_g: u8 = 0,
+    fg_b: u8 = 0,
+    bg_r: u8 = 0,
+    bg_g: u8 = 0,
+    bg_b: u8 = 0,
+    style_colors: u8 = 0,
+};
+
 comptime {
     std.debug.assert(@sizeOf(TerminalConfig) == config_bytes);
     std.debug.assert(@sizeOf(EncodedCell) == cell_bytes);
+    std.debug.assert(@sizeOf(EncodedState) == state_bytes);
 }
 
 const StyleState = struct {
@@ -153,7 +189,7 @@ const Handler = struct {
             .size_report => term.handleSizeReport(value),
             .device_attributes => term.handleDeviceAttributes(value),
             .full_reset => term.fullReset(),
-            .window_title,
+            .window_title => term.setTitle(value.title),
             .report_pwd,
             .xtversion,
             .kitty_keyboard_query,
@@ -213,6 +249,12 @@ const TerminalHandle = struct {
     pending_wrap: bool = false,
     origin_mode: bool = false,
     enable_left_right_margin: bool = false,
+    // Tracked for getState only: there is a single screen buffer, and the
+    // host encodes keys and pastes itself.
+    cursor_visible: bool = true,
+    alt_screen: bool = false,
+    cursor_keys: bool = false,
+    bracketed_paste: bool = false,
     style: StyleState = .{},
     last_codepoint: u21 = ' ',
     default_fg: Rgb = .{ .r = 169, .g = 177, .b = 214 },
@@ -223,6 +265,8 @@ const TerminalHandle = struct {
     dirty_rows: []bool,
     dirty_any: bool = true,
     responses: std.ArrayListUnmanaged(u8) = .empty,
+    title: std.ArrayListUnmanaged(u8) = .empty,
+    title_changed: bool = false,
     stream: Stream = undefined,
     scroll_top: usize = 0,
     scroll_bottom: usize = 0,
@@ -256,6 +300,7 @@ const TerminalHandle = struct {
     fn deinit(self: *TerminalHandle) void {
         self.stream.deinit();
         self.responses.deinit(alloc);
+        self.title.deinit(alloc);
         alloc.free(self.dirty_rows);
         alloc.free(self.cells);
     }
@@ -725,6 +770,10 @@ const TerminalHandle = struct {
                     self.scroll_right = self.cols - 1;
                 }
             },
+            .cursor_visible => self.cursor_visible = enabled,
+            .alt_screen_legacy, .alt_screen, .alt_screen_save_cursor_clear_enter => self.alt_screen = enabled,
+            .cursor_keys => self.cursor_keys = enabled,
+            .bracketed_paste => self.bracketed_paste = enabled,
             else => {},
         }
     }
@@ -826,6 +875,12 @@ const TerminalHandle = struct {
         self.responses.appendSlice(alloc, bytes) catch {};
     }
 
+    fn setTitle(self: *TerminalHandle, title: []const u8) void {
+        self.title.clearRetainingCapacity();
+        self.title.appendSlice(alloc, title) catch {};
+        self.title_changed = true;
+    }
+
     fn fullReset(self: *TerminalHandle) void {
         self.cursor_x = 0;
         self.cursor_y = 0;
@@ -835,6 +890,10 @@ const TerminalHandle = struct {
         self.wraparound = true;
         self.origin_mode = false;
         self.enable_left_right_margin = false;
+        self.cursor_visible = true;
+        self.alt_screen = false;
+        self.cursor_keys = false;
+        self.bracketed_paste = false;
         self.resetScrollRegion();
         self.style = .{};
         self.responses.clearRetainingCapacity();
@@ -842,6 +901,47 @@ const TerminalHandle = struct {
         self.markAllDirty();
     }
 
+    fn encodeState(self: *const TerminalHandle) EncodedState {
+        var state: EncodedState = .{
+            .cursor_x = @intCast(self.cursor_x),
+            .cursor_y = @intCast(self.cursor_y),
+            .saved_x = @intCast(self.saved_x),
+            .saved_y = @intCast(self.saved_y),
+            .scroll_top = @intCast(self.scroll_top),
+            .scroll_bottom = @intCast(self.scroll_bottom),
+            .scroll_left = @intCast(self.scroll_left),
+            .scroll_right = @intCast(self.scroll_right),
+        };
+        if (self.wraparound) state.flags |= state_flag_wraparound;
+        if (self.pending_wrap) state.flags |= state_flag_pending_wrap;
+        if (self.origin_mode) state.flags |= state_flag_origin;
+        if (self.enable_left_right_margin) state.flags |= state_flag_left_right_margin;
+        if (self.cursor_visible) state.flags |= state_flag_cursor_visible;
+        if (self.alt_screen) state.flags |= state_flag_alt_screen;
+        if (self.cursor_keys) state.flags |= state_flag_cursor_keys;
+        if (self.bracketed_paste) state.flags |= state_flag_bracketed_paste;
+
+        if (self.style.bold) state.style_flags |= flag_bold;
+        if (self.style.italic) state.style_flags |= flag_italic;
+        if (self.style.underline) state.style_flags |= flag_underline;
+        if (self.style.inverse) state.style_flags |= flag_inverse;
+        if (self.style.invisible) state.style_flags |= flag_invisible;
+        if (self.style.faint) state.style_flags |= flag_faint;
+        if (self.style.fg) |rgb| {
+            state.fg_r = rgb.r;
+            state.fg_g = rgb.g;
+            state.fg_b = rgb.b;
+            state.style_colors |= style_has_fg;
+        }
+        if (self.style.bg) |rgb| {
+            state.bg_r = rgb.r;
+            state.bg_g = rgb.g;
+            state.bg_b = rgb.b;
+            state.style_colors |= style_has_bg;
+        }
+        return state;
+    }
+
     fn blankCell(self: *TerminalHandle) EncodedCell {
         var cell: EncodedCell = .{};
         var bg = self.style.bg;
@@ -1105,6 +1205,49 @@ pub export fn ghostty_render_state_get_viewport(
     return size;
 }
 
+pub export fn ghostty_render_state_get_row(
+    handle: ?*TerminalHandle,
+    row: u32,
+    buffer_ptr: [*]u8,
+    buffer_len: usize,
+) usize {
+    const h = handle orelse return 0;
+    const idx: usize = @intCast(row);
+    if (idx >= h.rows) return 0;
+    const raw = std.mem.sliceAsBytes(h.rowSlice(idx));
+    const size = @min(raw.len, buffer_len);
+    if (size == 0) return 0;
+    std.mem.copyForwards(u8, buffer_ptr[0..size], raw[0..size]);
+    return size;
+}
+
+pub export fn ghostty_terminal_get_state(
+    handle: ?*TerminalHandle,
+    buffer_ptr: [*]u8,
+    buffer_len: usize,
+) usize {
+    const h = handle orelse return 0;
+    if (buffer_len < state_bytes) return 0;
+    const state = h.encodeState();
+    std.mem.copyForwards(u8, buffer_ptr[0..state_bytes], std.mem.asBytes(&state));
+    return state_bytes;
+}
+
+/// Copy the window title (OSC 0/2) if it changed since the last call.
+/// Returns -1 when unchanged; otherwise the number of bytes copied.
+pub export fn ghostty_terminal_take_title(
+    handle: ?*TerminalHandle,
+    buffer_ptr: [*]u8,
+    buffer_len: usize,
+) isize {
+    const h = handle orelse return -1;
+    if (!h.title_changed) return -1;
+    h.title_changed = false;
+    const n = @min(buffer_len, h.title.items.len);
+    std.mem.copyForwards(u8, buffer_ptr[0..n], h.title.items[0..n]);
+    return @intCast(n);
+}
+
 pub export fn ghostty_terminal_has_response(handle: ?*TerminalHandle) bool {
     const h = handle orelse return false;
     return h.responses.items.len > 0;